@@ -0,0 +1,220 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+
+/// Returns a short hex fingerprint of the given bytes.
+pub fn fingerprint(bytes: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Returns a fingerprint that changes whenever the file at `path` is
+/// modified. Based on the file size and modification time so that photos
+/// don't need to be read in full on every page render.
+pub fn file_version(path: &Path) -> String {
+    let Ok(meta) = std::fs::metadata(path) else {
+        return String::new();
+    };
+    let mut hasher = DefaultHasher::new();
+    meta.len().hash(&mut hasher);
+    if let Ok(modified) = meta.modified() {
+        modified.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+pub struct StaticAsset {
+    pub hash: String,
+    pub body: Vec<u8>,
+}
+
+/// Default static files compiled into the binary.
+const EMBEDDED: &[(&str, &[u8])] = &[
+    ("style.css", include_bytes!("../static/style.css")),
+    ("favicon.svg", include_bytes!("../static/favicon.svg")),
+];
+
+/// Static files loaded into memory at startup, keyed by filename.
+#[derive(Default)]
+pub struct StaticAssets {
+    files: HashMap<String, StaticAsset>,
+}
+
+impl StaticAssets {
+    /// Loads the embedded static files, replacing or extending them with the
+    /// files found in `override_dir`, if it exists.
+    pub fn load(override_dir: &Path) -> Self {
+        let mut files: HashMap<String, StaticAsset> = EMBEDDED
+            .iter()
+            .map(|(name, body)| {
+                let asset = StaticAsset {
+                    hash: fingerprint(body),
+                    body: body.to_vec(),
+                };
+                (name.to_string(), asset)
+            })
+            .collect();
+        let Ok(entries) = std::fs::read_dir(override_dir) else {
+            return StaticAssets { files };
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let Ok(body) = std::fs::read(&path) else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            let hash = fingerprint(&body);
+            files.insert(name, StaticAsset { hash, body });
+        }
+
+        StaticAssets { files }
+    }
+
+    /// Returns the fingerprinted URL for a static file, e.g.
+    /// `/static/style.0123456789abcdef.css`.
+    pub fn url(&self, name: &str) -> String {
+        match self.files.get(name) {
+            Some(asset) => format!("/static/{}", versioned_name(name, &asset.hash)),
+            None => format!("/static/{}", name),
+        }
+    }
+
+    /// Looks up a requested filename, which may or may not contain a
+    /// fingerprint. Returns the asset and whether the fingerprint matched
+    /// the current contents.
+    pub fn resolve(&self, requested: &str) -> Option<(&str, &StaticAsset, bool)> {
+        if let Some((name, asset)) = self.files.get_key_value(requested) {
+            return Some((name, asset, false));
+        }
+        let (name, hash) = strip_version(requested)?;
+        let (name, asset) = self.files.get_key_value(&name)?;
+        Some((name, asset, asset.hash == hash))
+    }
+}
+
+fn versioned_name(name: &str, hash: &str) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}.{}.{}", stem, hash, ext),
+        None => format!("{}.{}", name, hash),
+    }
+}
+
+/// Splits `style.abc123.css` into `("style.css", "abc123")`.
+fn strip_version(requested: &str) -> Option<(String, &str)> {
+    let (rest, ext) = requested.rsplit_once('.')?;
+    match rest.rsplit_once('.') {
+        Some((stem, hash)) => Some((format!("{}.{}", stem, ext), hash)),
+        None => Some((rest.to_string(), ext)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn fingerprint_is_stable() {
+        assert_eq!(fingerprint(b"hello"), fingerprint(b"hello"));
+        assert_ne!(fingerprint(b"hello"), fingerprint(b"world"));
+        assert_eq!(fingerprint(b"hello").len(), 16);
+    }
+
+    #[test]
+    fn file_version_missing_file() {
+        assert_eq!(file_version(Path::new("/nonexistent/photo.jpg")), "");
+    }
+
+    #[test]
+    fn file_version_changes_with_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photo.jpg");
+        fs::write(&path, b"one").unwrap();
+        let first = file_version(&path);
+        fs::write(&path, b"three").unwrap();
+        assert_ne!(first, file_version(&path));
+    }
+
+    #[test]
+    fn versioned_name_inserts_hash() {
+        assert_eq!(versioned_name("style.css", "abc"), "style.abc.css");
+        assert_eq!(versioned_name("LICENSE", "abc"), "LICENSE.abc");
+    }
+
+    #[test]
+    fn strip_version_splits_hash() {
+        assert_eq!(
+            strip_version("style.abc.css"),
+            Some(("style.css".to_string(), "abc"))
+        );
+        assert_eq!(
+            strip_version("LICENSE.abc"),
+            Some(("LICENSE".to_string(), "abc"))
+        );
+        assert_eq!(strip_version("LICENSE"), None);
+    }
+
+    #[test]
+    fn url_and_resolve_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("style.css"), b"body {}").unwrap();
+        let assets = StaticAssets::load(dir.path());
+
+        let url = assets.url("style.css");
+        let hash = fingerprint(b"body {}");
+        assert_eq!(url, format!("/static/style.{}.css", hash));
+
+        let requested = url.trim_start_matches("/static/");
+        let (name, asset, current) = assets.resolve(requested).unwrap();
+        assert_eq!(name, "style.css");
+        assert_eq!(asset.body, b"body {}");
+        assert!(current);
+    }
+
+    #[test]
+    fn resolve_unversioned_and_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("style.css"), b"body {}").unwrap();
+        let assets = StaticAssets::load(dir.path());
+
+        let (_, _, current) = assets.resolve("style.css").unwrap();
+        assert!(!current);
+        let (_, _, current) = assets.resolve("style.0000.css").unwrap();
+        assert!(!current);
+        assert!(assets.resolve("missing.css").is_none());
+    }
+
+    #[test]
+    fn load_embedded_defaults() {
+        let assets = StaticAssets::load(Path::new("/nonexistent/theme/static"));
+        let (_, style, _) = assets.resolve("style.css").unwrap();
+        assert_eq!(style.body, include_bytes!("../static/style.css"));
+        assert!(assets.resolve("favicon.svg").is_some());
+    }
+
+    #[test]
+    fn load_overrides_single_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("style.css"), b"body {}").unwrap();
+        let assets = StaticAssets::load(dir.path());
+        let (_, style, _) = assets.resolve("style.css").unwrap();
+        assert_eq!(style.body, b"body {}");
+        let (_, favicon, _) = assets.resolve("favicon.svg").unwrap();
+        assert_eq!(favicon.body, include_bytes!("../static/favicon.svg"));
+    }
+
+    #[test]
+    fn url_for_unknown_file() {
+        let assets = StaticAssets::default();
+        assert_eq!(assets.url("missing.css"), "/static/missing.css");
+    }
+}
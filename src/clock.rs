@@ -0,0 +1,310 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! Capture time corrections: the `time_offset` and `timezone` settings of an
+//! album, optionally per camera model, applied to the EXIF
+//! `DateTimeOriginal` of its photos.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{NaiveDateTime, Offset, TimeDelta, TimeZone as _};
+use serde::Deserialize;
+
+use crate::exif::{exif_field, read_exif};
+
+/// Parses an offset such as "+02:00", "-05:30" or "+00:01:30" into seconds.
+pub fn parse_offset(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => (1, value),
+    };
+    let parts: Vec<i64> = rest
+        .split(':')
+        .map(|p| p.parse().ok().filter(|_| p.len() == 2))
+        .collect::<Option<_>>()?;
+    let (hours, minutes, seconds) = match parts[..] {
+        [h, m] => (h, m, 0),
+        [h, m, s] => (h, m, s),
+        _ => return None,
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    Some(sign * (hours * 3600 + minutes * 60 + seconds))
+}
+
+/// Seconds added to the camera time, e.g. "+00:02:30" for a camera that is
+/// two and a half minutes slow.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(try_from = "String")]
+pub struct TimeOffset(pub i64);
+
+impl TryFrom<String> for TimeOffset {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_offset(&value)
+            .map(TimeOffset)
+            .ok_or_else(|| format!("invalid time offset: {}", value))
+    }
+}
+
+/// The time zone that a camera clock was set to: a fixed offset from UTC
+/// such as "+09:00", or a zone name such as "Asia/Tokyo" that also knows
+/// about daylight saving time.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(try_from = "String")]
+pub enum TimeZone {
+    Fixed(i64),
+    Named(chrono_tz::Tz),
+}
+
+impl TryFrom<String> for TimeZone {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if let Some(offset) = parse_offset(&value) {
+            return Ok(TimeZone::Fixed(offset));
+        }
+        value
+            .trim()
+            .parse()
+            .map(TimeZone::Named)
+            .map_err(|_| format!("unknown time zone: {}", value))
+    }
+}
+
+impl TimeZone {
+    /// Returns how many seconds the zone is ahead of UTC at a local time.
+    fn offset_at(self, local: NaiveDateTime) -> i64 {
+        match self {
+            TimeZone::Fixed(offset) => offset,
+            TimeZone::Named(tz) => {
+                // Times skipped by a daylight saving change get the offset
+                // from before the change, i.e. from the day before.
+                let offset = tz
+                    .offset_from_local_datetime(&local)
+                    .earliest()
+                    .unwrap_or_else(|| tz.offset_from_utc_datetime(&(local - TimeDelta::days(1))));
+                offset.fix().local_minus_utc().into()
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Default, Clone, Debug)]
+pub struct ClockSettings {
+    pub time_offset: Option<TimeOffset>,
+    pub timezone: Option<TimeZone>,
+    /// The earlier setting for matching GPX tracks, read as a fixed time
+    /// zone. `timezone` wins if both are set.
+    pub clock_offset: Option<TimeZone>,
+}
+
+impl ClockSettings {
+    fn timezone(&self) -> Option<TimeZone> {
+        self.timezone.or(self.clock_offset)
+    }
+}
+
+/// The clock settings of an album. `[cameras."X-T5"]` tables override
+/// them for the photos taken with a camera model.
+#[derive(Deserialize, Default, Clone, Debug)]
+pub struct Clock {
+    #[serde(flatten)]
+    pub album: ClockSettings,
+    #[serde(default)]
+    pub cameras: HashMap<String, ClockSettings>,
+}
+
+/// The corrected capture time of a photo.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CaptureTime {
+    /// Local time where the photo was taken, as shown on the pages.
+    pub local: NaiveDateTime,
+    /// The same moment in UTC, used for ordering photos. It's the local time
+    /// if the time zone is unknown.
+    pub utc: NaiveDateTime,
+}
+
+impl CaptureTime {
+    /// Formats the local time like `DateTimeOriginal`, "2026-02-01 15:01:06".
+    pub fn to_exif_string(self) -> String {
+        self.local.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+/// Returns the `DateTimeOriginal` of a photo as read from the camera clock.
+pub fn camera_time(exif: &exif::Exif) -> Option<NaiveDateTime> {
+    let taken = exif_field(exif, exif::Tag::DateTimeOriginal)?;
+    NaiveDateTime::parse_from_str(&taken, "%Y-%m-%d %H:%M:%S").ok()
+}
+
+impl Clock {
+    /// Returns the corrected capture time. The time zone comes from the
+    /// `OffsetTimeOriginal` recorded by the camera, or else the `timezone`
+    /// setting.
+    pub fn capture_time(&self, exif: &exif::Exif) -> Option<CaptureTime> {
+        let camera = camera_time(exif)?;
+        let model = exif_field(exif, exif::Tag::Model);
+        let settings = model.and_then(|m| self.cameras.get(&m));
+        let time_offset = settings
+            .and_then(|s| s.time_offset)
+            .or(self.album.time_offset);
+        let timezone = settings
+            .and_then(|s| s.timezone())
+            .or(self.album.timezone());
+
+        let local = camera + TimeDelta::seconds(time_offset.map_or(0, |o| o.0));
+        let offset = exif_field(exif, exif::Tag::OffsetTimeOriginal)
+            .and_then(|offset| parse_offset(&offset))
+            .or_else(|| timezone.map(|tz| tz.offset_at(local)))
+            .unwrap_or(0);
+        Some(CaptureTime {
+            local,
+            utc: local - TimeDelta::seconds(offset),
+        })
+    }
+
+    pub fn read(&self, path: &Path) -> Option<CaptureTime> {
+        self.capture_time(&read_exif(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/DSCF0199.jpg")
+    }
+
+    fn time(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse_offset("+02:00"), Some(7200));
+        assert_eq!(parse_offset("-05:30"), Some(-19800));
+        assert_eq!(parse_offset("00:01:30"), Some(90));
+        assert_eq!(parse_offset("+2"), None);
+        assert_eq!(parse_offset("+02:75"), None);
+        assert_eq!(parse_offset(""), None);
+    }
+
+    #[test]
+    fn parse_settings() {
+        let clock: Clock = toml::from_str(
+            "time_offset = \"-00:01:00\"\ntimezone = \"Asia/Tokyo\"\n\n[cameras.\"X-T5\"]\ntimezone = \"+02:00\"\n",
+        )
+        .unwrap();
+        assert_eq!(clock.album.time_offset, Some(TimeOffset(-60)));
+        assert_eq!(
+            clock.album.timezone,
+            Some(TimeZone::Named(chrono_tz::Asia::Tokyo))
+        );
+        assert_eq!(clock.cameras["X-T5"].timezone, Some(TimeZone::Fixed(7200)));
+
+        let clock: Clock = toml::from_str("clock_offset = \"-05:00\"").unwrap();
+        assert_eq!(clock.album.timezone(), Some(TimeZone::Fixed(-18000)));
+        let clock: Clock =
+            toml::from_str("clock_offset = \"-05:00\"\ntimezone = \"+01:00\"").unwrap();
+        assert_eq!(clock.album.timezone(), Some(TimeZone::Fixed(3600)));
+
+        assert!(toml::from_str::<Clock>("timezone = \"Mars/Olympus\"").is_err());
+        assert!(toml::from_str::<Clock>("time_offset = \"later\"").is_err());
+    }
+
+    #[test]
+    fn named_zone_follows_daylight_saving() {
+        let tz = TimeZone::Named(chrono_tz::Europe::Helsinki);
+        assert_eq!(tz.offset_at(time("2026-02-01 12:00:00")), 7200);
+        assert_eq!(tz.offset_at(time("2026-07-01 12:00:00")), 10800);
+        // 03:30 doesn't exist on the day the clocks go forward.
+        assert_eq!(tz.offset_at(time("2026-03-29 03:30:00")), 7200);
+    }
+
+    #[test]
+    fn capture_time_of_fixture() {
+        let taken = time("2026-02-01 15:01:06");
+        let capture = Clock::default().read(&fixture_path()).unwrap();
+        assert_eq!(
+            capture,
+            CaptureTime {
+                local: taken,
+                utc: taken
+            }
+        );
+        assert_eq!(capture.to_exif_string(), "2026-02-01 15:01:06");
+        assert!(
+            Clock::default()
+                .read(Path::new("/nonexistent/photo.jpg"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn offset_time_original_wins() {
+        let mut writer = exif::experimental::Writer::new();
+        let fields = [
+            (exif::Tag::DateTimeOriginal, "2026:02:01 15:01:06"),
+            (exif::Tag::OffsetTimeOriginal, "+09:00"),
+        ]
+        .map(|(tag, value)| exif::Field {
+            tag,
+            ifd_num: exif::In::PRIMARY,
+            value: exif::Value::Ascii(vec![value.as_bytes().to_vec()]),
+        });
+        for field in &fields {
+            writer.push_field(field);
+        }
+        let mut buf = std::io::Cursor::new(Vec::new());
+        writer.write(&mut buf, false).unwrap();
+        let exif = exif::Reader::new().read_raw(buf.into_inner()).unwrap();
+
+        let clock = Clock {
+            album: ClockSettings {
+                time_offset: None,
+                timezone: Some(TimeZone::Fixed(7200)),
+                clock_offset: None,
+            },
+            cameras: HashMap::new(),
+        };
+        let capture = clock.capture_time(&exif).unwrap();
+        assert_eq!(capture.utc, time("2026-02-01 06:01:06"));
+    }
+
+    #[test]
+    fn album_and_camera_settings() {
+        let mut clock = Clock {
+            album: ClockSettings {
+                time_offset: Some(TimeOffset(-6)),
+                timezone: Some(TimeZone::Named(chrono_tz::Europe::Helsinki)),
+                clock_offset: None,
+            },
+            cameras: HashMap::new(),
+        };
+        let capture = clock.read(&fixture_path()).unwrap();
+        assert_eq!(capture.local, time("2026-02-01 15:01:00"));
+        assert_eq!(capture.utc, time("2026-02-01 13:01:00"));
+
+        // The camera settings win, falling back to the album's.
+        clock.cameras.insert(
+            "X-T5".to_string(),
+            ClockSettings {
+                time_offset: Some(TimeOffset(54)),
+                timezone: None,
+                clock_offset: None,
+            },
+        );
+        let capture = clock.read(&fixture_path()).unwrap();
+        assert_eq!(capture.local, time("2026-02-01 15:02:00"));
+        assert_eq!(capture.utc, time("2026-02-01 13:02:00"));
+    }
+}
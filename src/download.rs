@@ -0,0 +1,165 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use axum::body::{Body, Bytes};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+type Chunk = Result<Bytes, io::Error>;
+
+/// A `Write` that forwards its output to an async channel in chunks, so that
+/// an archive written on a blocking thread can be streamed as a response
+/// body.
+struct ChannelWriter {
+    tx: mpsc::Sender<Chunk>,
+    buf: Vec<u8>,
+}
+
+impl ChannelWriter {
+    fn send(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = Bytes::from(std::mem::take(&mut self.buf));
+        self.tx
+            .blocking_send(Ok(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "client disconnected"))
+    }
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        if self.buf.len() >= CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send()
+    }
+}
+
+/// Returns `name`, or if it's taken, the first of "a-2.jpg", "a-3.jpg" and
+/// so on that isn't. Converted photos can end up with the same name, e.g.
+/// `a.png` and the PNG thumbnail of `a.gif`.
+fn unique_name(used: &mut HashSet<String>, name: String) -> String {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) => (stem, format!(".{}", extension)),
+        None => (name.as_str(), String::new()),
+    };
+    let unique = (1..)
+        .map(|n| match n {
+            1 => name.clone(),
+            n => format!("{}-{}{}", stem, n, extension),
+        })
+        .find(|candidate| !used.contains(candidate))
+        .unwrap();
+    used.insert(unique.clone());
+    unique
+}
+
+/// Writes the named files into an uncompressed ZIP archive. Photos are
+/// already compressed, so storing them as-is keeps this cheap.
+fn write_zip<W, F>(out: W, names: &[String], path_for: F) -> zip::result::ZipResult<()>
+where
+    W: Write,
+    F: Fn(&str) -> io::Result<(String, PathBuf)>,
+{
+    let mut zip = ZipWriter::new_stream(out);
+    let mut used = HashSet::new();
+    for name in names {
+        let (entry_name, path) = path_for(name)?;
+        let entry_name = unique_name(&mut used, entry_name);
+        let mut file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len();
+        let options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .large_file(len >= u32::MAX as u64);
+        zip.start_file(entry_name, options)?;
+        io::copy(&mut file, &mut zip)?;
+    }
+    zip.finish()?.flush()?;
+    Ok(())
+}
+
+/// Streams a ZIP archive of the named files as a response body. The archive
+/// is built on the fly on a blocking thread; `path_for` resolves each name to
+/// the name of the archive entry and the file to include, and may generate
+/// the file.
+pub fn stream_zip<F>(names: Vec<String>, path_for: F) -> Body
+where
+    F: Fn(&str) -> io::Result<(String, PathBuf)> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(4);
+    tokio::task::spawn_blocking(move || {
+        let mut writer = ChannelWriter {
+            tx,
+            buf: Vec::with_capacity(CHUNK_SIZE),
+        };
+        if let Err(err) = write_zip(&mut writer, &names, path_for) {
+            tracing::warn!("failed to write ZIP archive: {}", err);
+            // Fail the response instead of ending it with a truncated archive.
+            let _ = writer.tx.blocking_send(Err(io::Error::other(err)));
+        }
+    });
+    Body::from_stream(ReceiverStream::new(rx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Read;
+
+    #[test]
+    fn write_zip_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.jpg"), b"first").unwrap();
+        fs::write(dir.path().join("b.jpg"), b"second").unwrap();
+        let names = vec!["a.jpg".to_string(), "b.jpg".to_string()];
+
+        let mut buf = Vec::new();
+        write_zip(&mut buf, &names, |name| {
+            Ok((name.to_uppercase(), dir.path().join(name)))
+        })
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(io::Cursor::new(buf)).unwrap();
+        assert_eq!(archive.len(), 2);
+        let mut contents = String::new();
+        archive
+            .by_name("B.JPG")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "second");
+    }
+
+    #[test]
+    fn unique_names() {
+        let mut used = HashSet::new();
+        let names = ["a.jpg", "a.jpg", "a.jpg", "a-2.jpg", "b"]
+            .map(|name| unique_name(&mut used, name.to_string()));
+        assert_eq!(names, ["a.jpg", "a-2.jpg", "a-3.jpg", "a-2-2.jpg", "b"]);
+    }
+
+    #[test]
+    fn write_zip_missing_file() {
+        let names = vec!["nope.jpg".to_string()];
+        let result = write_zip(Vec::new(), &names, |name| {
+            Ok((name.to_string(), PathBuf::from("/nonexistent").join(name)))
+        });
+        assert!(result.is_err());
+    }
+}
@@ -2,9 +2,17 @@
 //
 // SPDX-License-Identifier: MIT
 
+use std::io::{BufReader, Read, Seek};
 use std::path::Path;
 
-#[derive(Default)]
+use serde::Serialize;
+
+use crate::i18n::{ExifStrings, Language, Strings};
+use crate::makernote::{self, DynamicRange, Grain, GrainSize, MakerNote, Strength};
+use crate::raw;
+use crate::timespan::{Date, day_month_year};
+
+#[derive(Default, Serialize)]
 pub struct ExifInfo {
     pub camera: Option<String>,
     pub lens: Option<String>,
@@ -12,6 +20,30 @@ pub struct ExifInfo {
     pub aperture: Option<String>,
     pub exposure: Option<String>,
     pub iso: Option<String>,
+    /// Capture date and time, with the UTC offset if it's known.
+    pub date_taken: Option<String>,
+    /// Place name from the reverse geocoder, filled in by the photo page.
+    pub location: Option<String>,
+    pub exposure_compensation: Option<String>,
+    pub exposure_program: Option<String>,
+    pub metering_mode: Option<String>,
+    pub flash: Option<String>,
+    pub white_balance: Option<String>,
+    pub software: Option<String>,
+    pub artist: Option<String>,
+    pub copyright: Option<String>,
+    /// IPTC credit line, e.g. the agency.
+    pub credit: Option<String>,
+    /// IPTC keywords, comma-separated.
+    pub keywords: Option<String>,
+    /// Pixel dimensions recorded by the camera, e.g. "7728 × 5152".
+    pub dimensions: Option<String>,
+    /// Settings from the MakerNote, so far only for Fujifilm cameras.
+    pub film_simulation: Option<String>,
+    pub grain_effect: Option<String>,
+    pub color_chrome: Option<String>,
+    pub color_chrome_blue: Option<String>,
+    pub dynamic_range: Option<String>,
 }
 
 impl ExifInfo {
@@ -43,15 +75,216 @@ impl ExifInfo {
         if !settings_str.is_empty() {
             parts.push(&settings_str);
         }
+        if let Some(film_simulation) = &self.film_simulation {
+            parts.push(film_simulation);
+        }
 
         parts.join(" · ")
     }
+
+    /// Label and value of each known field, for the details panel.
+    pub fn details(&self, t: &Strings) -> Vec<(&'static str, String)> {
+        let t = &t.exif;
+        let fields = [
+            (t.date_taken, self.date_taken.clone()),
+            (t.location, self.location.clone()),
+            (t.camera, self.camera.clone()),
+            (t.lens, self.lens.clone()),
+            (t.focal_length, self.focal_length.clone()),
+            (
+                t.aperture,
+                self.aperture.as_ref().map(|ap| format!("\u{192}/{}", ap)),
+            ),
+            (
+                t.exposure,
+                self.exposure.as_ref().map(|ex| format!("{} s", ex)),
+            ),
+            (t.iso, self.iso.clone()),
+            (t.exposure_compensation, self.exposure_compensation.clone()),
+            (t.exposure_program, self.exposure_program.clone()),
+            (t.metering_mode, self.metering_mode.clone()),
+            (t.flash, self.flash.clone()),
+            (t.white_balance, self.white_balance.clone()),
+            (t.film_simulation, self.film_simulation.clone()),
+            (t.grain_effect, self.grain_effect.clone()),
+            (t.color_chrome, self.color_chrome.clone()),
+            (t.color_chrome_blue, self.color_chrome_blue.clone()),
+            (t.dynamic_range, self.dynamic_range.clone()),
+            (t.dimensions, self.dimensions.clone()),
+            (t.software, self.software.clone()),
+            (t.artist, self.artist.clone()),
+            (t.credit, self.credit.clone()),
+            (t.copyright, self.copyright.clone()),
+            (t.keywords, self.keywords.clone()),
+        ];
+        fields
+            .into_iter()
+            .filter_map(|(label, value)| Some((label, value?)))
+            .collect()
+    }
+
+    /// Adds the credit and keywords from IPTC, and the by-line and copyright
+    /// notice unless EXIF has an artist and copyright.
+    pub fn add_iptc(&mut self, iptc: &Iptc) {
+        if self.artist.is_none() {
+            self.artist = iptc.byline.clone();
+        }
+        if self.copyright.is_none() {
+            self.copyright = iptc.copyright.clone();
+        }
+        self.credit = iptc.credit.clone();
+        if !iptc.keywords.is_empty() {
+            self.keywords = Some(iptc.keywords.join(", "));
+        }
+    }
+}
+
+/// IPTC-IIM metadata from the APP13 segment of a JPEG.
+#[derive(Default, Debug, PartialEq)]
+pub struct Iptc {
+    /// Caption/Abstract, 2:120.
+    pub caption: Option<String>,
+    /// Headline, 2:105.
+    pub headline: Option<String>,
+    /// By-line, 2:80. Several photographers are joined with commas.
+    pub byline: Option<String>,
+    /// Credit, 2:110.
+    pub credit: Option<String>,
+    /// Copyright Notice, 2:116.
+    pub copyright: Option<String>,
+    /// Keywords, 2:25.
+    pub keywords: Vec<String>,
+}
+
+/// Reads the IPTC metadata of a JPEG. Other files have none.
+pub fn read_iptc(path: &Path) -> Iptc {
+    let Ok(file) = std::fs::File::open(path) else {
+        return Iptc::default();
+    };
+    let resources = photoshop_resources(&mut BufReader::new(file)).unwrap_or_default();
+    parse_iim(&image_resource(&resources, IPTC_NAA).unwrap_or_default())
+}
+
+/// Identifies the APP13 segments that hold Photoshop image resources.
+const PHOTOSHOP: &[u8] = b"Photoshop 3.0\0";
+/// Image resource ID of the IPTC-NAA record.
+const IPTC_NAA: u16 = 0x0404;
+
+/// Returns the Photoshop image resources from the APP13 segments of a JPEG,
+/// concatenated as they may be split across segments.
+fn photoshop_resources(reader: &mut (impl Read + Seek)) -> Option<Vec<u8>> {
+    let mut resources = Vec::new();
+    let mut marker = [0u8; 2];
+    reader.read_exact(&mut marker).ok()?;
+    if marker != [0xFF, 0xD8] {
+        return None;
+    }
+    loop {
+        reader.read_exact(&mut marker).ok()?;
+        if marker[0] != 0xFF {
+            return None;
+        }
+        // Start of scan or end of image: the metadata segments are over.
+        if matches!(marker[1], 0xDA | 0xD9) {
+            break;
+        }
+        let mut length = [0u8; 2];
+        reader.read_exact(&mut length).ok()?;
+        let length = u16::from_be_bytes(length).checked_sub(2)? as usize;
+        if marker[1] == 0xED {
+            let mut segment = vec![0; length];
+            reader.read_exact(&mut segment).ok()?;
+            if let Some(data) = segment.strip_prefix(PHOTOSHOP) {
+                resources.extend_from_slice(data);
+            }
+        } else {
+            reader.seek_relative(length as i64).ok()?;
+        }
+    }
+    Some(resources)
+}
+
+/// Finds an image resource: "8BIM", the ID, a Pascal string name padded to
+/// an even length, and the size and data, also padded to an even length.
+fn image_resource(data: &[u8], id: u16) -> Option<Vec<u8>> {
+    let mut rest = data;
+    while let Some(resource) = rest.strip_prefix(b"8BIM") {
+        let resource_id = u16::from_be_bytes(resource.get(..2)?.try_into().ok()?);
+        let name_len = *resource.get(2)? as usize;
+        let start = 2 + (1 + name_len).next_multiple_of(2);
+        let size = u32::from_be_bytes(resource.get(start..start + 4)?.try_into().ok()?) as usize;
+        let body = resource.get(start + 4..start + 4 + size)?;
+        if resource_id == id {
+            return Some(body.to_vec());
+        }
+        rest = resource.get(start + 4 + size.next_multiple_of(2)..)?;
+    }
+    None
+}
+
+/// Parses the datasets of the IPTC application record, record 2. Text is
+/// read as UTF-8 if it's valid, and as Latin-1 otherwise.
+fn parse_iim(data: &[u8]) -> Iptc {
+    let mut iptc = Iptc::default();
+    let mut bylines = Vec::new();
+    let mut rest = data;
+    while let [0x1C, record, dataset, size_hi, size_lo, tail @ ..] = rest {
+        // Extended sizes are only used for binary data such as previews.
+        if size_hi & 0x80 != 0 {
+            break;
+        }
+        let size = u16::from_be_bytes([*size_hi, *size_lo]) as usize;
+        let Some(value) = tail.get(..size) else {
+            break;
+        };
+        rest = &tail[size..];
+        if *record != 2 {
+            continue;
+        }
+        let text = match std::str::from_utf8(value) {
+            Ok(text) => text.trim().to_string(),
+            Err(_) => value
+                .iter()
+                .map(|&b| b as char)
+                .collect::<String>()
+                .trim()
+                .to_string(),
+        };
+        if text.is_empty() {
+            continue;
+        }
+        let field = match dataset {
+            25 => {
+                iptc.keywords.push(text);
+                continue;
+            }
+            80 => {
+                bylines.push(text);
+                continue;
+            }
+            105 => &mut iptc.headline,
+            110 => &mut iptc.credit,
+            116 => &mut iptc.copyright,
+            120 => &mut iptc.caption,
+            _ => continue,
+        };
+        field.get_or_insert(text);
+    }
+    if !bylines.is_empty() {
+        iptc.byline = Some(bylines.join(", "));
+    }
+    iptc
 }
 
 pub fn read_exif(path: &Path) -> Option<exif::Exif> {
     let file = std::fs::File::open(path).ok()?;
     let mut bufreader = std::io::BufReader::new(file);
-    exif::Reader::new().read_from_container(&mut bufreader).ok()
+    let exif = exif::Reader::new().read_from_container(&mut bufreader).ok();
+    if exif.is_none() && raw::is_raw(path) {
+        // RAF files aren't TIFF containers, but their preview has the EXIF.
+        return exif::Reader::new().read_raw(raw::preview_exif(path)?).ok();
+    }
+    exif
 }
 
 fn clean_exif_value(raw: &str) -> Option<String> {
@@ -68,7 +301,7 @@ pub fn exif_field(exif: &exif::Exif, tag: exif::Tag) -> Option<String> {
     clean_exif_value(&field.display_value().to_string())
 }
 
-fn camera_name(make: Option<String>, model: Option<String>) -> Option<String> {
+pub fn camera_name(make: Option<String>, model: Option<String>) -> Option<String> {
     match (make, model) {
         (Some(make), Some(model)) => {
             if model.starts_with(&make) {
@@ -83,51 +316,181 @@ fn camera_name(make: Option<String>, model: Option<String>) -> Option<String> {
     }
 }
 
-pub fn read_exif_info(path: &Path) -> ExifInfo {
+/// Returns the text of an ASCII field. Fields with several strings, such as
+/// a copyright with separate photographer and editor parts, are joined.
+fn ascii_field(exif: &exif::Exif, tag: exif::Tag) -> Option<String> {
+    let field = exif.get_field(tag, exif::In::PRIMARY)?;
+    let exif::Value::Ascii(parts) = &field.value else {
+        return None;
+    };
+    let parts: Vec<String> = parts
+        .iter()
+        .map(|p| String::from_utf8_lossy(p).trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" / "))
+}
+
+fn uint_field(exif: &exif::Exif, tag: exif::Tag) -> Option<u32> {
+    exif.get_field(tag, exif::In::PRIMARY)?.value.get_uint(0)
+}
+
+pub fn read_exif_info(path: &Path, lang: Language) -> ExifInfo {
     let Some(exif) = read_exif(path) else {
         return ExifInfo::default();
     };
+    let t = &lang.strings().exif;
 
     let camera = camera_name(
         exif_field(&exif, exif::Tag::Make),
         exif_field(&exif, exif::Tag::Model),
     );
 
-    ExifInfo {
+    let dimensions = match (
+        uint_field(&exif, exif::Tag::PixelXDimension),
+        uint_field(&exif, exif::Tag::PixelYDimension),
+    ) {
+        (Some(width), Some(height)) => Some(format!("{} × {}", width, height)),
+        _ => None,
+    };
+
+    let maker_note = exif
+        .get_field(exif::Tag::MakerNote, exif::In::PRIMARY)
+        .and_then(|field| match &field.value {
+            exif::Value::Undefined(data, _) => {
+                makernote::parse(&exif_field(&exif, exif::Tag::Make)?, data)
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let mut info = ExifInfo {
         camera,
         lens: exif_field(&exif, exif::Tag::LensModel),
         focal_length: exif_field(&exif, exif::Tag::FocalLength).map(|fl| format!("{} mm", fl)),
         aperture: exif_field(&exif, exif::Tag::FNumber),
         exposure: exif_field(&exif, exif::Tag::ExposureTime),
         iso: exif_field(&exif, exif::Tag::PhotographicSensitivity),
+        date_taken: exif_field(&exif, exif::Tag::DateTimeOriginal).and_then(|date| {
+            let offset = exif_field(&exif, exif::Tag::OffsetTimeOriginal);
+            format_date_taken(&date, offset.as_deref(), lang)
+        }),
+        exposure_compensation: exif
+            .get_field(exif::Tag::ExposureBiasValue, exif::In::PRIMARY)
+            .and_then(|field| match &field.value {
+                exif::Value::SRational(v) => v.first().map(|r| r.to_f64()),
+                _ => None,
+            })
+            .map(|ev| format_exposure_compensation(ev, lang)),
+        exposure_program: uint_field(&exif, exif::Tag::ExposureProgram)
+            .and_then(|p| t.exposure_programs.get(p as usize))
+            .map(|p| p.to_string()),
+        metering_mode: uint_field(&exif, exif::Tag::MeteringMode)
+            .map(|m| metering_mode(t, m).to_string()),
+        flash: uint_field(&exif, exif::Tag::Flash).map(|flash| {
+            if flash & 1 == 1 {
+                t.flash_fired.to_string()
+            } else {
+                t.flash_not_fired.to_string()
+            }
+        }),
+        white_balance: uint_field(&exif, exif::Tag::WhiteBalance).and_then(|wb| match wb {
+            0 => Some(t.white_balance_auto.to_string()),
+            1 => Some(t.white_balance_manual.to_string()),
+            _ => None,
+        }),
+        software: ascii_field(&exif, exif::Tag::Software),
+        artist: ascii_field(&exif, exif::Tag::Artist),
+        copyright: ascii_field(&exif, exif::Tag::Copyright),
+        dimensions,
+        ..Default::default()
+    };
+    add_maker_note(&mut info, &maker_note, t);
+    info
+}
+
+fn add_maker_note(info: &mut ExifInfo, note: &MakerNote, t: &ExifStrings) {
+    let strength = |s: Strength| match s {
+        Strength::Off => t.off,
+        Strength::Weak => t.weak,
+        Strength::Strong => t.strong,
+    };
+
+    info.film_simulation = note.film_simulation.map(str::to_string);
+    info.grain_effect = note
+        .grain_effect
+        .map(|Grain { strength: s, size }| match size {
+            Some(GrainSize::Small) => format!("{}, {}", strength(s), t.small),
+            Some(GrainSize::Large) => format!("{}, {}", strength(s), t.large),
+            None => strength(s).to_string(),
+        });
+    info.color_chrome = note.color_chrome.map(|s| strength(s).to_string());
+    info.color_chrome_blue = note.color_chrome_blue.map(|s| strength(s).to_string());
+    info.dynamic_range = note
+        .dynamic_range
+        .and_then(|DynamicRange { auto, percent }| match (auto, percent) {
+            (true, Some(percent)) => Some(format!("{} (DR{})", t.auto, percent)),
+            (true, None) => Some(t.auto.to_string()),
+            (false, Some(percent)) => Some(format!("DR{}", percent)),
+            (false, None) => None,
+        });
+}
+
+fn metering_mode(t: &ExifStrings, mode: u32) -> &'static str {
+    let other = t.metering_modes[t.metering_modes.len() - 1];
+    match mode {
+        0..=6 => t.metering_modes[mode as usize],
+        _ => other,
+    }
+}
+
+/// Formats an exposure bias like "+0.7 EV" or "−1 EV", with the decimal
+/// separator of the language.
+fn format_exposure_compensation(ev: f64, lang: Language) -> String {
+    let rounded = (ev * 10.0).round() / 10.0;
+    let sign = if rounded > 0.0 {
+        "+"
+    } else if rounded < 0.0 {
+        "\u{2212}"
+    } else {
+        ""
+    };
+    let mut number = format!("{}", rounded.abs());
+    if lang != Language::En {
+        number = number.replace('.', ",");
     }
+    format!("{}{} EV", sign, number)
 }
 
-pub fn read_exif_date(path: &Path) -> Option<String> {
-    let exif = read_exif(path)?;
-    exif_field(&exif, exif::Tag::DateTimeOriginal)
+/// Formats an EXIF date and time, e.g. "1 February 2026, 15:01 (UTC+02:00)".
+fn format_date_taken(datetime_str: &str, offset: Option<&str>, lang: Language) -> Option<String> {
+    let date = Date::parse(datetime_str)?;
+    let (_, time) = datetime_str.split_once(' ')?;
+    let mut parts = time.split(':');
+    let hour = parts.next()?;
+    let minute = parts.next()?;
+
+    let mut out = match lang {
+        Language::En | Language::De => {
+            format!("{}, {}:{}", day_month_year(lang, date), hour, minute)
+        }
+        Language::Fi => format!("{} klo {}.{}", day_month_year(lang, date), hour, minute),
+        Language::Sv => format!("{} {}:{}", day_month_year(lang, date), hour, minute),
+    };
+    if let Some(offset) = offset {
+        out.push_str(&format!(" (UTC{})", offset));
+    }
+    Some(out)
 }
 
-pub fn format_year_month(datetime_str: &str) -> String {
+pub fn format_year_month(datetime_str: &str, lang: Language) -> String {
     // EXIF date format: "2024-06-15 12:00:00" or "2024:06:15 12:00:00"
     let parts: Vec<&str> = datetime_str.split(['-', ':', ' ']).collect();
     if parts.len() >= 2 {
         let year = parts[0];
         let month_num: u32 = parts[1].parse().unwrap_or(0);
-        let month_name = match month_num {
-            1 => "January",
-            2 => "February",
-            3 => "March",
-            4 => "April",
-            5 => "May",
-            6 => "June",
-            7 => "July",
-            8 => "August",
-            9 => "September",
-            10 => "October",
-            11 => "November",
-            12 => "December",
-            _ => return datetime_str.to_string(),
+        let Some(month_name) = lang.month_name(month_num) else {
+            return datetime_str.to_string();
         };
         format!("{} {}", month_name, year)
     } else {
@@ -146,7 +509,7 @@ mod tests {
 
     #[test]
     fn read_exif_info_from_jpeg() {
-        let info = read_exif_info(&fixture_path());
+        let info = read_exif_info(&fixture_path(), Language::En);
 
         assert_eq!(info.camera.as_deref(), Some("FUJIFILM X-T5"));
         assert_eq!(
@@ -160,14 +523,115 @@ mod tests {
     }
 
     #[test]
-    fn read_exif_date_from_jpeg() {
-        let date = read_exif_date(&fixture_path());
-        assert_eq!(date.as_deref(), Some("2026-02-01 15:01:06"));
+    fn read_exif_details_from_jpeg() {
+        let info = read_exif_info(&fixture_path(), Language::En);
+
+        assert_eq!(info.date_taken.as_deref(), Some("1 February 2026, 15:01"));
+        assert_eq!(info.exposure_compensation.as_deref(), Some("0 EV"));
+        assert_eq!(info.exposure_program.as_deref(), Some("Aperture priority"));
+        assert_eq!(info.metering_mode.as_deref(), Some("Pattern"));
+        assert_eq!(info.flash.as_deref(), Some("Did not fire"));
+        assert_eq!(info.white_balance.as_deref(), Some("Auto"));
+        assert_eq!(info.software.as_deref(), Some("Capture One 23 Macintosh"));
+        assert!(info.artist.is_none());
+        assert!(info.copyright.is_none());
+        assert_eq!(info.dimensions.as_deref(), Some("2048 × 1365"));
+    }
+
+    #[test]
+    fn read_exif_details_localized() {
+        let info = read_exif_info(&fixture_path(), Language::Fi);
+
+        assert_eq!(info.date_taken.as_deref(), Some("1.2.2026 klo 15.01"));
+        assert_eq!(info.exposure_program.as_deref(), Some("Aukon esivalinta"));
+        assert_eq!(info.flash.as_deref(), Some("Ei välähtänyt"));
+    }
+
+    #[test]
+    fn details_skip_missing_fields() {
+        let info = ExifInfo {
+            aperture: Some("2.8".to_string()),
+            exposure: Some("1/250".to_string()),
+            copyright: Some("Miikka Koskinen".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            info.details(Language::En.strings()),
+            vec![
+                ("Aperture", "\u{192}/2.8".to_string()),
+                ("Shutter speed", "1/250 s".to_string()),
+                ("Copyright", "Miikka Koskinen".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn add_fujifilm_maker_note() {
+        let note = makernote::parse(
+            "FUJIFILM",
+            &makernote::tests::fujifilm_note(&[
+                (0x1047, 64),
+                (0x104c, 16),
+                (0x1048, 32),
+                (0x1401, 0x600),
+                (0x1402, 0),
+                (0x1403, 200),
+            ]),
+        )
+        .unwrap();
+        let mut info = ExifInfo {
+            camera: Some("FUJIFILM X-T5".to_string()),
+            ..Default::default()
+        };
+        add_maker_note(&mut info, &note, &Language::En.strings().exif);
+
+        assert_eq!(info.film_simulation.as_deref(), Some("Classic Chrome"));
+        assert_eq!(info.grain_effect.as_deref(), Some("Strong, Small"));
+        assert_eq!(info.color_chrome.as_deref(), Some("Weak"));
+        assert!(info.color_chrome_blue.is_none());
+        assert_eq!(info.dynamic_range.as_deref(), Some("Auto (DR200)"));
+        assert_eq!(info.summary(), "FUJIFILM X-T5 · Classic Chrome");
+
+        add_maker_note(&mut info, &note, &Language::De.strings().exif);
+        assert_eq!(info.grain_effect.as_deref(), Some("Stark, Klein"));
+    }
+
+    #[test]
+    fn format_date_taken_with_offset() {
+        assert_eq!(
+            format_date_taken("2024-06-15 09:05:00", Some("+03:00"), Language::De).as_deref(),
+            Some("15. Juni 2024, 09:05 (UTC+03:00)")
+        );
+        assert!(format_date_taken("garbage", None, Language::En).is_none());
+    }
+
+    #[test]
+    fn format_exposure_compensation_values() {
+        assert_eq!(
+            format_exposure_compensation(2.0 / 3.0, Language::En),
+            "+0.7 EV"
+        );
+        assert_eq!(
+            format_exposure_compensation(-1.0, Language::En),
+            "\u{2212}1 EV"
+        );
+        assert_eq!(
+            format_exposure_compensation(-4.0 / 3.0, Language::Sv),
+            "\u{2212}1,3 EV"
+        );
+        assert_eq!(format_exposure_compensation(0.0, Language::En), "0 EV");
+    }
+
+    #[test]
+    fn metering_mode_other() {
+        let t = &Language::En.strings().exif;
+        assert_eq!(metering_mode(t, 2), "Center-weighted average");
+        assert_eq!(metering_mode(t, 255), "Other");
     }
 
     #[test]
     fn read_exif_info_missing_file() {
-        let info = read_exif_info(Path::new("/nonexistent/photo.jpg"));
+        let info = read_exif_info(Path::new("/nonexistent/photo.jpg"), Language::En);
 
         assert!(info.camera.is_none());
         assert!(info.lens.is_none());
@@ -178,13 +642,23 @@ mod tests {
     }
 
     #[test]
-    fn read_exif_date_missing_file() {
-        assert!(read_exif_date(Path::new("/nonexistent/photo.jpg")).is_none());
+    fn read_exif_info_from_raf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("DSCF0199.RAF");
+        std::fs::write(&path, raw::tests::make_raf(&raw::tests::fixture_jpeg())).unwrap();
+
+        let info = read_exif_info(&path, Language::En);
+        assert_eq!(info.camera.as_deref(), Some("FUJIFILM X-T5"));
+        let exif = read_exif(&path).unwrap();
+        assert_eq!(
+            exif_field(&exif, exif::Tag::DateTimeOriginal).as_deref(),
+            Some("2026-02-01 15:01:06")
+        );
     }
 
     #[test]
     fn summary_all_fields() {
-        let info = read_exif_info(&fixture_path());
+        let info = read_exif_info(&fixture_path(), Language::En);
         let summary = info.summary();
 
         assert!(summary.contains("FUJIFILM X-T5"));
@@ -225,7 +699,7 @@ mod tests {
         // When model already starts with make, don't repeat it.
         // This is what FUJIFILM does: Make="FUJIFILM", Model="X-T5"
         // so result should be "FUJIFILM X-T5", not "FUJIFILM FUJIFILM X-T5"
-        let info = read_exif_info(&fixture_path());
+        let info = read_exif_info(&fixture_path(), Language::En);
         assert_eq!(info.camera.as_deref(), Some("FUJIFILM X-T5"));
     }
 
@@ -260,12 +734,18 @@ mod tests {
 
     #[test]
     fn format_year_month_colon_separated() {
-        assert_eq!(format_year_month("2026:02:01 15:01:06"), "February 2026");
+        assert_eq!(
+            format_year_month("2026:02:01 15:01:06", Language::En),
+            "February 2026"
+        );
     }
 
     #[test]
     fn format_year_month_dash_separated() {
-        assert_eq!(format_year_month("2024-06-15 12:00:00"), "June 2024");
+        assert_eq!(
+            format_year_month("2024-06-15 12:00:00", Language::En),
+            "June 2024"
+        );
     }
 
     #[test]
@@ -283,19 +763,31 @@ mod tests {
             ("2024:12:15 12:00:00", "December 2024"),
         ];
         for (input, output) in expected {
-            assert_eq!(format_year_month(input), output);
+            assert_eq!(format_year_month(input, Language::En), output);
         }
     }
 
+    #[test]
+    fn format_year_month_localized() {
+        assert_eq!(
+            format_year_month("2024:06:15 12:00:00", Language::Fi),
+            "kesäkuu 2024"
+        );
+        assert_eq!(
+            format_year_month("2024:06:15 12:00:00", Language::De),
+            "Juni 2024"
+        );
+    }
+
     #[test]
     fn format_year_month_invalid() {
-        assert_eq!(format_year_month("garbage"), "garbage");
+        assert_eq!(format_year_month("garbage", Language::En), "garbage");
     }
 
     #[test]
     fn format_year_month_invalid_month() {
         assert_eq!(
-            format_year_month("2024:13:01 00:00:00"),
+            format_year_month("2024:13:01 00:00:00", Language::En),
             "2024:13:01 00:00:00"
         );
     }
@@ -325,4 +817,109 @@ mod tests {
         let exif = read_exif(&fixture_path()).unwrap();
         assert!(exif_field(&exif, exif::Tag::GPSLatitude).is_none());
     }
+
+    fn dataset(record: u8, dataset: u8, value: &[u8]) -> Vec<u8> {
+        let mut out = vec![0x1C, record, dataset];
+        out.extend((value.len() as u16).to_be_bytes());
+        out.extend(value);
+        out
+    }
+
+    /// Adds an APP13 segment with the IPTC datasets after the start of image.
+    fn with_iptc(jpeg: &[u8], iim: &[u8]) -> Vec<u8> {
+        let mut resources = PHOTOSHOP.to_vec();
+        // Another resource first, with a name that needs padding.
+        resources.extend(b"8BIM\x04\x0c\x02ab\x00\x00\x00\x00\x01x\x00");
+        resources.extend(b"8BIM\x04\x04\x00\x00");
+        resources.extend((iim.len() as u32).to_be_bytes());
+        resources.extend(iim);
+        if iim.len() % 2 == 1 {
+            resources.push(0);
+        }
+        let mut out = jpeg[..2].to_vec();
+        out.extend([0xFF, 0xED]);
+        out.extend((resources.len() as u16 + 2).to_be_bytes());
+        out.extend(resources);
+        out.extend(&jpeg[2..]);
+        out
+    }
+
+    #[test]
+    fn parse_iim_datasets() {
+        let iim = [
+            dataset(1, 90, b"\x1b%G"),
+            dataset(2, 105, "Sunset over Töölönlahti".as_bytes()),
+            dataset(2, 120, b"The bay at dusk.  "),
+            dataset(2, 80, b"A. Photographer"),
+            dataset(2, 80, b"B. Assistant"),
+            dataset(2, 110, b"Agency"),
+            // Latin-1 for the copyright sign.
+            dataset(2, 116, b"\xa9 2026 A. Photographer"),
+            dataset(2, 25, b"sunset"),
+            dataset(2, 25, b"helsinki"),
+            dataset(2, 25, b""),
+        ]
+        .concat();
+        assert_eq!(
+            parse_iim(&iim),
+            Iptc {
+                caption: Some("The bay at dusk.".to_string()),
+                headline: Some("Sunset over Töölönlahti".to_string()),
+                byline: Some("A. Photographer, B. Assistant".to_string()),
+                credit: Some("Agency".to_string()),
+                copyright: Some("© 2026 A. Photographer".to_string()),
+                keywords: vec!["sunset".to_string(), "helsinki".to_string()],
+            }
+        );
+
+        // A truncated dataset ends the parsing.
+        let mut iim = dataset(2, 105, b"Headline");
+        iim.extend([0x1C, 2, 120, 0, 50, b'x']);
+        let iptc = parse_iim(&iim);
+        assert_eq!(iptc.headline.as_deref(), Some("Headline"));
+        assert_eq!(iptc.caption, None);
+    }
+
+    #[test]
+    fn read_iptc_from_jpeg() {
+        let jpeg = std::fs::read(fixture_path()).unwrap();
+        let iim = [dataset(2, 105, b"Headline"), dataset(2, 25, b"x")].concat();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.jpg");
+        std::fs::write(&path, with_iptc(&jpeg, &iim)).unwrap();
+
+        let iptc = read_iptc(&path);
+        assert_eq!(iptc.headline.as_deref(), Some("Headline"));
+        assert_eq!(iptc.keywords, ["x"]);
+        // The EXIF data is still found.
+        let info = read_exif_info(&path, Language::En);
+        assert_eq!(info.camera.as_deref(), Some("FUJIFILM X-T5"));
+
+        assert_eq!(read_iptc(&fixture_path()), Iptc::default());
+        assert_eq!(
+            read_iptc(Path::new("/nonexistent/photo.jpg")),
+            Iptc::default()
+        );
+        std::fs::write(&path, b"not a real jpeg").unwrap();
+        assert_eq!(read_iptc(&path), Iptc::default());
+    }
+
+    #[test]
+    fn add_iptc_keeps_exif_artist() {
+        let mut info = ExifInfo {
+            artist: Some("EXIF Artist".to_string()),
+            ..Default::default()
+        };
+        info.add_iptc(&Iptc {
+            byline: Some("IPTC By-line".to_string()),
+            copyright: Some("IPTC Copyright".to_string()),
+            credit: Some("Agency".to_string()),
+            keywords: vec!["a".to_string(), "b".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(info.artist.as_deref(), Some("EXIF Artist"));
+        assert_eq!(info.copyright.as_deref(), Some("IPTC Copyright"));
+        assert_eq!(info.credit.as_deref(), Some("Agency"));
+        assert_eq!(info.keywords.as_deref(), Some("a, b"));
+    }
 }
@@ -0,0 +1,152 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! Image formats beyond JPEG, PNG and WebP are enabled with cargo features.
+//! Browsers can't show all of them, so their thumbnails are stored as JPEG,
+//! or as PNG for GIFs to keep the transparency.
+
+use std::path::Path;
+
+use image::{ColorType, DynamicImage};
+
+/// Extensions of the images that are listed in albums, not counting RAW
+/// files.
+pub const EXTENSIONS: &[&str] = &[
+    "jpg",
+    "jpeg",
+    "png",
+    "webp",
+    #[cfg(feature = "tiff")]
+    "tif",
+    #[cfg(feature = "tiff")]
+    "tiff",
+    #[cfg(feature = "gif")]
+    "gif",
+    #[cfg(feature = "avif")]
+    "avif",
+];
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+}
+
+pub fn is_image(path: &Path) -> bool {
+    extension(path).is_some_and(|e| EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Name of the thumbnail file of `filename`, with an extension that tells the
+/// format it's stored in.
+pub fn thumbnail_name(filename: &str) -> String {
+    match extension(Path::new(filename)).as_deref() {
+        Some("jpg" | "jpeg" | "png" | "webp") => filename.to_string(),
+        Some("gif") => format!("{}.png", filename),
+        _ => format!("{}.jpg", filename),
+    }
+}
+
+/// Returns true for GIF and WebP files with more than one frame. Browsers
+/// play these when served as-is, so the photo page shows the original
+/// instead of the still thumbnail.
+pub fn is_animated(path: &Path) -> bool {
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let reader = std::io::BufReader::new(file);
+    match extension(path).as_deref() {
+        Some("webp") => image::codecs::webp::WebPDecoder::new(reader)
+            .is_ok_and(|decoder| decoder.has_animation()),
+        #[cfg(feature = "gif")]
+        Some("gif") => {
+            use image::AnimationDecoder;
+            image::codecs::gif::GifDecoder::new(reader)
+                .is_ok_and(|decoder| decoder.into_frames().take(2).count() > 1)
+        }
+        _ => false,
+    }
+}
+
+/// Converts a thumbnail to something its file format can store: 8 bits per
+/// channel, and no alpha channel in JPEG. Deeper images such as 16-bit film
+/// scans are tone-mapped by scaling the channel values linearly into the
+/// 8-bit range.
+pub fn to_storable(img: DynamicImage, path: &Path) -> DynamicImage {
+    let jpeg = matches!(extension(path).as_deref(), Some("jpg" | "jpeg"));
+    match img.color() {
+        ColorType::Rgb8 | ColorType::L8 => img,
+        ColorType::Rgba8 | ColorType::La8 if !jpeg => img,
+        color if color.has_alpha() && !jpeg => DynamicImage::ImageRgba8(img.into_rgba8()),
+        _ => DynamicImage::ImageRgb8(img.into_rgb8()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb, Rgba};
+
+    #[test]
+    fn thumbnail_names() {
+        assert_eq!(thumbnail_name("a.JPG"), "a.JPG");
+        assert_eq!(thumbnail_name("a.webp"), "a.webp");
+        assert_eq!(thumbnail_name("scan.tif"), "scan.tif.jpg");
+        assert_eq!(thumbnail_name("anim.gif"), "anim.gif.png");
+    }
+
+    #[test]
+    fn to_storable_16_bit() {
+        let img = DynamicImage::ImageRgb16(ImageBuffer::from_pixel(2, 2, Rgb([65535, 32896, 0])));
+        let stored = to_storable(img, Path::new("scan.tif.jpg"));
+        assert_eq!(stored.color(), ColorType::Rgb8);
+        assert_eq!(stored.to_rgb8().get_pixel(0, 0), &Rgb([255, 128, 0]));
+    }
+
+    #[test]
+    fn to_storable_alpha() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_pixel(2, 2, Rgba([1, 2, 3, 4])));
+        assert_eq!(
+            to_storable(img.clone(), Path::new("a.png")).color(),
+            ColorType::Rgba8
+        );
+        assert_eq!(
+            to_storable(img, Path::new("a.jpg")).color(),
+            ColorType::Rgb8
+        );
+    }
+
+    #[test]
+    fn still_webp_is_not_animated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.webp");
+        DynamicImage::new_rgb8(4, 4).save(&path).unwrap();
+        assert!(!is_animated(&path));
+        assert!(!is_animated(Path::new("/nonexistent/a.gif")));
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn animated_gif() {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, Frame, RgbaImage};
+
+        let dir = tempfile::tempdir().unwrap();
+        let frames = |count: u8| {
+            (0..count).map(|i| {
+                Frame::from_parts(
+                    RgbaImage::from_pixel(4, 4, Rgba([i * 100, 0, 0, 255])),
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(100, 1),
+                )
+            })
+        };
+        for (name, count) in [("still.gif", 1), ("anim.gif", 2)] {
+            let file = std::fs::File::create(dir.path().join(name)).unwrap();
+            GifEncoder::new(file).encode_frames(frames(count)).unwrap();
+        }
+        assert!(!is_animated(&dir.path().join("still.gif")));
+        assert!(is_animated(&dir.path().join("anim.gif")));
+    }
+}
@@ -0,0 +1,322 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! Photo locations from the EXIF GPS tags or the album's GPX tracks, served
+//! as GeoJSON for the map page.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::Clock;
+use crate::exif::read_exif;
+use crate::gpx::{Track, capture_time};
+
+/// Default URL template of the map tiles, served from the `tiles` directory
+/// in the data directory.
+pub const LOCAL_TILES: &str = "/tiles/{z}/{x}/{y}.png";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    /// Decimal degrees, positive to the north.
+    pub latitude: f64,
+    /// Decimal degrees, positive to the east.
+    pub longitude: f64,
+    /// Meters above sea level.
+    pub altitude: Option<f64>,
+}
+
+/// The `location` setting of an album.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum AlbumLocation {
+    /// `true` uses the GPS position of each photo; `false` leaves the
+    /// album's photos off the map.
+    Gps(bool),
+    /// Latitude and longitude used for all photos of the album, e.g.
+    /// `[60.17, 24.94]`.
+    Fixed([f64; 2]),
+}
+
+impl Default for AlbumLocation {
+    fn default() -> Self {
+        AlbumLocation::Gps(true)
+    }
+}
+
+/// Finds the positions of the photos of an album: from the album's
+/// `location` setting, the GPS data of the photo, or the album's GPX tracks,
+/// in that order.
+pub struct Locator {
+    location: AlbumLocation,
+    track: Option<Track>,
+    clock: Clock,
+}
+
+impl Locator {
+    pub fn new(album_path: &Path, location: AlbumLocation, clock: Clock) -> Locator {
+        let track = match location {
+            AlbumLocation::Gps(true) => Track::load(album_path),
+            _ => None,
+        };
+        Locator {
+            location,
+            track,
+            clock,
+        }
+    }
+
+    /// True if the album's photos are kept off the map.
+    pub fn is_hidden(&self) -> bool {
+        self.location == AlbumLocation::Gps(false)
+    }
+
+    /// The GPX files that the positions come from.
+    pub fn files(&self) -> &[PathBuf] {
+        self.track.as_ref().map_or(&[], |track| &track.files)
+    }
+
+    pub fn position(&self, path: &Path) -> Option<Position> {
+        match self.location {
+            AlbumLocation::Gps(false) => None,
+            AlbumLocation::Fixed([latitude, longitude]) => Some(Position {
+                latitude,
+                longitude,
+                altitude: None,
+            }),
+            AlbumLocation::Gps(true) => {
+                let exif = read_exif(path)?;
+                read_position(&exif).or_else(|| {
+                    let time = capture_time(&exif, &self.clock)?;
+                    self.track.as_ref()?.position_at(time)
+                })
+            }
+        }
+    }
+}
+
+/// Reads the GPS position. Returns `None` if the latitude or longitude is
+/// missing or out of range.
+pub fn read_position(exif: &exif::Exif) -> Option<Position> {
+    let mut latitude = degrees(exif, exif::Tag::GPSLatitude)?;
+    let mut longitude = degrees(exif, exif::Tag::GPSLongitude)?;
+    if reference(exif, exif::Tag::GPSLatitudeRef) == Some(b'S') {
+        latitude = -latitude;
+    }
+    if reference(exif, exif::Tag::GPSLongitudeRef) == Some(b'W') {
+        longitude = -longitude;
+    }
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return None;
+    }
+
+    let altitude = exif
+        .get_field(exif::Tag::GPSAltitude, exif::In::PRIMARY)
+        .and_then(|field| match &field.value {
+            exif::Value::Rational(v) => v.first().map(|r| r.to_f64()),
+            _ => None,
+        })
+        .filter(|a| a.is_finite())
+        .map(|altitude| {
+            // 1 means below sea level.
+            let below = exif
+                .get_field(exif::Tag::GPSAltitudeRef, exif::In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0))
+                == Some(1);
+            if below { -altitude } else { altitude }
+        });
+
+    Some(Position {
+        latitude,
+        longitude,
+        altitude,
+    })
+}
+
+/// Converts degrees, minutes and seconds to decimal degrees.
+fn degrees(exif: &exif::Exif, tag: exif::Tag) -> Option<f64> {
+    let field = exif.get_field(tag, exif::In::PRIMARY)?;
+    let exif::Value::Rational(v) = &field.value else {
+        return None;
+    };
+    let part = |i: usize| v.get(i).map_or(0.0, |r| r.to_f64());
+    let degrees = part(0) + part(1) / 60.0 + part(2) / 3600.0;
+    (!v.is_empty() && degrees.is_finite()).then_some(degrees)
+}
+
+fn reference(exif: &exif::Exif, tag: exif::Tag) -> Option<u8> {
+    match &exif.get_field(tag, exif::In::PRIMARY)?.value {
+        exif::Value::Ascii(v) => v.first()?.first().map(|c| c.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+/// A GeoJSON `FeatureCollection` of photo locations.
+#[derive(Serialize)]
+pub struct FeatureCollection {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    features: Vec<Feature>,
+}
+
+impl FeatureCollection {
+    pub fn new(features: Vec<Feature>) -> FeatureCollection {
+        FeatureCollection {
+            kind: "FeatureCollection",
+            features,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Feature {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    geometry: Point,
+    properties: PhotoProperties,
+}
+
+impl Feature {
+    pub fn new(position: Position, properties: PhotoProperties) -> Feature {
+        // GeoJSON puts the longitude first.
+        let mut coordinates = vec![position.longitude, position.latitude];
+        coordinates.extend(position.altitude);
+        Feature {
+            kind: "Feature",
+            geometry: Point {
+                kind: "Point",
+                coordinates,
+            },
+            properties,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Point {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    coordinates: Vec<f64>,
+}
+
+#[derive(Serialize)]
+pub struct PhotoProperties {
+    pub album: String,
+    pub album_title: String,
+    pub filename: String,
+    /// URL of the photo page.
+    pub url: String,
+    /// URL of the small thumbnail.
+    pub thumbnail: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exif::{Field, In, Rational, Tag, Value};
+
+    fn field(tag: Tag, value: Value) -> Field {
+        Field {
+            tag,
+            ifd_num: In::PRIMARY,
+            value,
+        }
+    }
+
+    fn dms(d: u32, m: u32, s: (u32, u32)) -> Value {
+        Value::Rational(vec![
+            Rational::from((d, 1)),
+            Rational::from((m, 1)),
+            Rational::from(s),
+        ])
+    }
+
+    /// Builds an EXIF blob with the given GPS fields.
+    fn exif_with(fields: &[Field]) -> exif::Exif {
+        let mut writer = exif::experimental::Writer::new();
+        for f in fields {
+            writer.push_field(f);
+        }
+        let mut buf = std::io::Cursor::new(Vec::new());
+        writer.write(&mut buf, false).unwrap();
+        exif::Reader::new().read_raw(buf.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn read_position_south_west() {
+        let exif = exif_with(&[
+            field(Tag::GPSLatitudeRef, Value::Ascii(vec![b"S".to_vec()])),
+            field(Tag::GPSLatitude, dms(33, 51, (3600, 100))),
+            field(Tag::GPSLongitudeRef, Value::Ascii(vec![b"W".to_vec()])),
+            field(Tag::GPSLongitude, dms(70, 30, (0, 1))),
+            field(Tag::GPSAltitudeRef, Value::Byte(vec![1])),
+            field(
+                Tag::GPSAltitude,
+                Value::Rational(vec![Rational::from((25, 2))]),
+            ),
+        ]);
+        let position = read_position(&exif).unwrap();
+        assert!((position.latitude - -33.86).abs() < 1e-9);
+        assert_eq!(position.longitude, -70.5);
+        assert_eq!(position.altitude, Some(-12.5));
+    }
+
+    #[test]
+    fn read_position_without_altitude() {
+        let exif = exif_with(&[
+            field(Tag::GPSLatitudeRef, Value::Ascii(vec![b"N".to_vec()])),
+            field(Tag::GPSLatitude, dms(60, 10, (12, 1))),
+            field(Tag::GPSLongitudeRef, Value::Ascii(vec![b"E".to_vec()])),
+            field(Tag::GPSLongitude, dms(24, 56, (24, 1))),
+        ]);
+        let position = read_position(&exif).unwrap();
+        assert!((position.latitude - 60.17).abs() < 1e-9);
+        assert!((position.longitude - 24.94).abs() < 1e-9);
+        assert_eq!(position.altitude, None);
+    }
+
+    #[test]
+    fn read_position_missing_or_invalid() {
+        let exif = exif_with(&[field(Tag::GPSLatitude, dms(60, 0, (0, 1)))]);
+        assert!(read_position(&exif).is_none());
+
+        let exif = exif_with(&[
+            field(Tag::GPSLatitude, dms(95, 0, (0, 1))),
+            field(Tag::GPSLongitude, dms(24, 0, (0, 1))),
+        ]);
+        assert!(read_position(&exif).is_none());
+    }
+
+    #[test]
+    fn album_location_setting() {
+        #[derive(Deserialize)]
+        struct Meta {
+            location: AlbumLocation,
+        }
+        let parse = |s: &str| toml::from_str::<Meta>(s).unwrap().location;
+        assert_eq!(parse("location = false"), AlbumLocation::Gps(false));
+        assert_eq!(
+            parse("location = [60.17, 24.94]"),
+            AlbumLocation::Fixed([60.17, 24.94])
+        );
+    }
+
+    #[test]
+    fn feature_coordinates() {
+        let position = Position {
+            latitude: 60.17,
+            longitude: 24.94,
+            altitude: Some(10.0),
+        };
+        let properties = PhotoProperties {
+            album: "a".to_string(),
+            album_title: "A".to_string(),
+            filename: "1.jpg".to_string(),
+            url: "/album/a/1.jpg".to_string(),
+            thumbnail: "/thumbs/a/small/1.jpg".to_string(),
+        };
+        let feature = Feature::new(position, properties);
+        assert_eq!(feature.geometry.coordinates, vec![24.94, 60.17, 10.0]);
+    }
+}
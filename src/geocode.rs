@@ -0,0 +1,347 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! Offline reverse geocoding: turns photo positions into place names such as
+//! "Helsinki, Finland" using a GeoNames cities dump in the `geonames`
+//! directory of the data directory. The results are cached per photo.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::geo::Position;
+use crate::is_stale;
+
+/// Places farther than this from the photo are not used.
+const MAX_DISTANCE_KM: f64 = 100.0;
+const EARTH_RADIUS_KM: f64 = 6371.0;
+/// Length of a degree of latitude, and of longitude at the equator.
+const KM_PER_DEGREE: f64 = 111.2;
+
+struct City {
+    name: String,
+    country: String,
+    latitude: f64,
+    longitude: f64,
+}
+
+pub struct Geocoder {
+    cities: Vec<City>,
+    /// Indices of the cities by whole degrees of latitude and longitude.
+    grid: HashMap<(i32, i32), Vec<usize>>,
+    /// The data files, used to invalidate cached place names.
+    files: Vec<PathBuf>,
+}
+
+fn cell(latitude: f64, longitude: f64) -> (i32, i32) {
+    (latitude.floor() as i32, longitude.floor() as i32)
+}
+
+/// Returns the longitudes of the grid columns within `MAX_DISTANCE_KM` of a
+/// position. Degrees of longitude get shorter toward the poles, so more
+/// columns are needed there, up to all of them.
+fn columns(latitude: f64, longitude: i32) -> Vec<i32> {
+    // The widest span is needed at the poleward edge of the search area.
+    let edge = (latitude.abs() + MAX_DISTANCE_KM / KM_PER_DEGREE).min(90.0);
+    let km_per_degree = KM_PER_DEGREE * edge.to_radians().cos();
+    let span = (MAX_DISTANCE_KM / km_per_degree).ceil();
+    if !span.is_finite() || span >= 180.0 {
+        return (-180..180).collect();
+    }
+    let span = span as i32;
+    // Wrap around the antimeridian.
+    (-span..=span)
+        .map(|d| (longitude + d + 180).rem_euclid(360) - 180)
+        .collect()
+}
+
+/// Great-circle distance in kilometers.
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lon1) = (a.0.to_radians(), a.1.to_radians());
+    let (lat2, lon2) = (b.0.to_radians(), b.1.to_radians());
+    let h = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+/// Reads country names from a GeoNames `countryInfo.txt`, keyed by ISO code.
+fn load_countries(path: &Path) -> HashMap<String, String> {
+    let Ok(data) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    data.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split('\t').collect();
+            Some((columns.first()?.to_string(), columns.get(4)?.to_string()))
+        })
+        .collect()
+}
+
+/// Parses a line of a GeoNames cities file: name in column 1, latitude and
+/// longitude in columns 4 and 5, and the country code in column 8.
+fn parse_city(line: &str, countries: &HashMap<String, String>) -> Option<City> {
+    let columns: Vec<&str> = line.split('\t').collect();
+    let name = columns.get(1)?.trim();
+    let latitude: f64 = columns.get(4)?.parse().ok()?;
+    let longitude: f64 = columns.get(5)?.parse().ok()?;
+    let code = columns.get(8)?.trim();
+    if name.is_empty() || !latitude.is_finite() || !longitude.is_finite() {
+        return None;
+    }
+    Some(City {
+        name: name.to_string(),
+        country: countries
+            .get(code)
+            .cloned()
+            .unwrap_or_else(|| code.to_string()),
+        latitude,
+        longitude,
+    })
+}
+
+impl Geocoder {
+    /// Loads the `cities*.txt` files in `dir`, with country names from
+    /// `countryInfo.txt` if there is one. Returns `None` if there are no
+    /// cities.
+    pub fn load(dir: &Path) -> Option<Geocoder> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                name.starts_with("cities") && name.ends_with(".txt")
+            })
+            .collect();
+        files.sort();
+
+        let country_info = dir.join("countryInfo.txt");
+        let countries = load_countries(&country_info);
+        let mut cities = Vec::new();
+        for file in &files {
+            let Ok(data) = std::fs::read_to_string(file) else {
+                continue;
+            };
+            cities.extend(data.lines().filter_map(|line| parse_city(line, &countries)));
+        }
+        if cities.is_empty() {
+            return None;
+        }
+        if country_info.is_file() {
+            files.push(country_info);
+        }
+
+        let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+        for (i, city) in cities.iter().enumerate() {
+            grid.entry(cell(city.latitude, city.longitude))
+                .or_default()
+                .push(i);
+        }
+        Some(Geocoder {
+            cities,
+            grid,
+            files,
+        })
+    }
+
+    pub fn city_count(&self) -> usize {
+        self.cities.len()
+    }
+
+    /// Returns the name and country of the nearest city, e.g. "Helsinki,
+    /// Finland".
+    pub fn place(&self, position: Position) -> Option<String> {
+        let point = (position.latitude, position.longitude);
+        let (lat, lon) = cell(position.latitude, position.longitude);
+        let rows = (MAX_DISTANCE_KM / KM_PER_DEGREE).ceil() as i32;
+        let columns = columns(position.latitude, lon);
+        let mut nearest: Option<(f64, &City)> = None;
+        for dlat in -rows..=rows {
+            for &lon in &columns {
+                let Some(indices) = self.grid.get(&(lat + dlat, lon)) else {
+                    continue;
+                };
+                for &i in indices {
+                    let city = &self.cities[i];
+                    let d = distance(point, (city.latitude, city.longitude));
+                    if d <= MAX_DISTANCE_KM && nearest.is_none_or(|(best, _)| d < best) {
+                        nearest = Some((d, city));
+                    }
+                }
+            }
+        }
+        let (_, city) = nearest?;
+        Some(format!("{}, {}", city.name, city.country))
+    }
+
+    /// Returns the place name of a photo, using the cached one if it's newer
+    /// than `sources` and the GeoNames data. `position` is only called when
+    /// the place has to be looked up.
+    pub fn cached_place(
+        &self,
+        cache_dir: &Path,
+        album: &str,
+        filename: &str,
+        sources: &[PathBuf],
+        position: impl FnOnce() -> Option<Position>,
+    ) -> Option<String> {
+        let path = cache_dir
+            .join(album)
+            .join("place")
+            .join(format!("{}.txt", filename));
+        let fresh = path.is_file()
+            && !sources
+                .iter()
+                .chain(&self.files)
+                .any(|source| is_stale(source, &path));
+        if fresh && let Ok(place) = std::fs::read_to_string(&path) {
+            // An empty file means that there's no place for the photo.
+            return Some(place).filter(|p| !p.is_empty());
+        }
+
+        let place = position().and_then(|position| self.place(position));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        std::fs::write(&path, place.as_deref().unwrap_or_default()).ok();
+        place
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CITIES: &str = "\
+658225\tHelsinki\tHelsinki\t\t60.16952\t24.93545\tP\tPPLC\tFI\t\t01\t091\t\t\t558457\t\t26\tEurope/Helsinki\t2023-01-01
+660129\tEspoo\tEspoo\t\t60.2052\t24.6522\tP\tPPLA3\tFI\t\t01\t049\t\t\t256760\t\t19\tEurope/Helsinki\t2023-01-01
+588409\tTallinn\tTallinn\t\t59.43696\t24.75353\tP\tPPLC\tEE\t\t01\t\t\t\t394024\t\t34\tEurope/Tallinn\t2023-01-01
+2198148\tWaiyevo\tWaiyevo\t\t-16.8\t179.9\tP\tPPL\tFJ\t\t\t\t\t\t1000\t\t\tPacific/Fiji\t2023-01-01
+";
+
+    const COUNTRIES: &str = "\
+#ISO\tISO3\tISO-Numeric\tfips\tCountry\tCapital
+FI\tFIN\t246\tFI\tFinland\tHelsinki
+EE\tEST\t233\tEN\tEstonia\tTallinn
+";
+
+    fn position(latitude: f64, longitude: f64) -> Position {
+        Position {
+            latitude,
+            longitude,
+            altitude: None,
+        }
+    }
+
+    fn geocoder(countries: bool) -> (tempfile::TempDir, Geocoder) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("cities15000.txt"), CITIES).unwrap();
+        if countries {
+            std::fs::write(dir.path().join("countryInfo.txt"), COUNTRIES).unwrap();
+        }
+        let geocoder = Geocoder::load(dir.path()).unwrap();
+        (dir, geocoder)
+    }
+
+    #[test]
+    fn nearest_city() {
+        let (_dir, geocoder) = geocoder(true);
+        assert_eq!(geocoder.city_count(), 4);
+        assert_eq!(
+            geocoder.place(position(60.17, 24.95)).as_deref(),
+            Some("Helsinki, Finland")
+        );
+        assert_eq!(
+            geocoder.place(position(60.21, 24.66)).as_deref(),
+            Some("Espoo, Finland")
+        );
+        assert_eq!(
+            geocoder.place(position(59.5, 24.7)).as_deref(),
+            Some("Tallinn, Estonia")
+        );
+        // Too far from any city.
+        assert_eq!(geocoder.place(position(65.0, 25.5)), None);
+    }
+
+    #[test]
+    fn country_code_without_country_info() {
+        let (_dir, geocoder) = geocoder(false);
+        assert_eq!(
+            geocoder.place(position(60.17, 24.95)).as_deref(),
+            Some("Helsinki, FI")
+        );
+    }
+
+    #[test]
+    fn across_the_antimeridian() {
+        let (_dir, geocoder) = geocoder(false);
+        assert_eq!(
+            geocoder.place(position(-16.8, -179.9)).as_deref(),
+            Some("Waiyevo, FJ")
+        );
+    }
+
+    #[test]
+    fn far_north_searches_wider() {
+        let dir = tempfile::tempdir().unwrap();
+        // At 65°N a degree of longitude is only 47 km, so Eastby is two
+        // grid cells east of the position but nearer than Northby, which is
+        // in the same cell.
+        std::fs::write(
+            dir.path().join("cities1000.txt"),
+            "1\tNorthby\tNorthby\t\t65.85\t25.5\tP\tPPL\tFI\n\
+             2\tEastby\tEastby\t\t65.0\t27.3\tP\tPPL\tFI\n",
+        )
+        .unwrap();
+        let geocoder = Geocoder::load(dir.path()).unwrap();
+        assert_eq!(
+            geocoder.place(position(65.0, 25.5)).as_deref(),
+            Some("Eastby, FI")
+        );
+        // Close to the pole every column is searched.
+        assert_eq!(columns(89.5, 10).len(), 360);
+        assert_eq!(columns(0.0, 179), [178, 179, -180]);
+    }
+
+    #[test]
+    fn load_without_cities() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("countryInfo.txt"), COUNTRIES).unwrap();
+        assert!(Geocoder::load(dir.path()).is_none());
+        assert!(Geocoder::load(&dir.path().join("missing")).is_none());
+    }
+
+    #[test]
+    fn cached_place() {
+        let (_dir, geocoder) = geocoder(true);
+        let cache = tempfile::tempdir().unwrap();
+        let lookup = || {
+            geocoder.cached_place(cache.path(), "album", "a.jpg", &[], || {
+                Some(position(60.17, 24.95))
+            })
+        };
+        assert_eq!(lookup().as_deref(), Some("Helsinki, Finland"));
+        let cached = cache.path().join("album/place/a.jpg.txt");
+        assert_eq!(
+            std::fs::read_to_string(&cached).unwrap(),
+            "Helsinki, Finland"
+        );
+
+        // The cached name is used without looking up the position.
+        let place = geocoder.cached_place(cache.path(), "album", "a.jpg", &[], || {
+            panic!("should use the cache")
+        });
+        assert_eq!(place.as_deref(), Some("Helsinki, Finland"));
+
+        // No place is cached too.
+        assert_eq!(
+            geocoder.cached_place(cache.path(), "album", "b.jpg", &[], || None),
+            None
+        );
+        assert_eq!(
+            std::fs::read_to_string(cache.path().join("album/place/b.jpg.txt")).unwrap(),
+            ""
+        );
+    }
+}
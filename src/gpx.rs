@@ -0,0 +1,230 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! Geotagging from GPX tracks: photos without GPS data get a position
+//! interpolated from the track points of the `.gpx` files in their album,
+//! matched by capture time.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDateTime};
+
+use crate::clock::Clock;
+use crate::geo::Position;
+
+/// Positions are interpolated between track points at most this many
+/// seconds apart. A longer gap means that the logger was off.
+const MAX_GAP: i64 = 15 * 60;
+/// Outside the track and across gaps, the nearest track point is used if
+/// it's at most this many seconds away.
+const MAX_DISTANCE: i64 = 5 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TrackPoint {
+    /// Seconds since the Unix epoch.
+    time: i64,
+    latitude: f64,
+    longitude: f64,
+    elevation: Option<f64>,
+}
+
+/// The track points of all GPX files of an album, in time order.
+pub struct Track {
+    points: Vec<TrackPoint>,
+    /// The GPX files, used to invalidate cached place names.
+    pub files: Vec<PathBuf>,
+}
+
+fn parse_time(text: &str) -> Option<i64> {
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.timestamp());
+    }
+    // Times without a zone are UTC in GPX.
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|time| time.and_utc().timestamp())
+}
+
+/// Reads the track points that have a time.
+fn parse(xml: &str) -> Vec<TrackPoint> {
+    let Ok(doc) = roxmltree::Document::parse(xml) else {
+        return Vec::new();
+    };
+    doc.descendants()
+        .filter(|node| node.has_tag_name("trkpt"))
+        .filter_map(|node| {
+            let child = |name: &str| {
+                node.children()
+                    .find(|c| c.has_tag_name(name))
+                    .and_then(|c| c.text())
+            };
+            let latitude: f64 = node.attribute("lat")?.trim().parse().ok()?;
+            let longitude: f64 = node.attribute("lon")?.trim().parse().ok()?;
+            if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+                return None;
+            }
+            Some(TrackPoint {
+                time: parse_time(child("time")?)?,
+                latitude,
+                longitude,
+                elevation: child("ele").and_then(|e| e.trim().parse().ok()),
+            })
+        })
+        .collect()
+}
+
+impl Track {
+    /// Loads the `.gpx` files in the album directory. Returns `None` if
+    /// there are no track points.
+    pub fn load(album_path: &Path) -> Option<Track> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(album_path)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case("gpx"))
+            })
+            .collect();
+        files.sort();
+
+        let mut points: Vec<TrackPoint> = files
+            .iter()
+            .filter_map(|file| std::fs::read_to_string(file).ok())
+            .flat_map(|xml| parse(&xml))
+            .collect();
+        if points.is_empty() {
+            return None;
+        }
+        points.sort_by_key(|p| p.time);
+        Some(Track { points, files })
+    }
+
+    /// Returns the position at the given time, interpolated between the
+    /// surrounding track points.
+    pub fn position_at(&self, time: i64) -> Option<Position> {
+        let i = self.points.partition_point(|p| p.time < time);
+        let after = self.points.get(i);
+        let before = i.checked_sub(1).map(|i| &self.points[i]);
+        let point = match (before, after) {
+            (_, Some(a)) if a.time == time => *a,
+            (Some(b), Some(a)) if a.time - b.time <= MAX_GAP => {
+                let f = (time - b.time) as f64 / (a.time - b.time) as f64;
+                let lerp = |x: f64, y: f64| x + (y - x) * f;
+                TrackPoint {
+                    time,
+                    latitude: lerp(b.latitude, a.latitude),
+                    longitude: lerp(b.longitude, a.longitude),
+                    elevation: b.elevation.zip(a.elevation).map(|(x, y)| lerp(x, y)),
+                }
+            }
+            _ => *[before, after]
+                .into_iter()
+                .flatten()
+                .filter(|p| (p.time - time).abs() <= MAX_DISTANCE)
+                .min_by_key(|p| (p.time - time).abs())?,
+        };
+        Some(Position {
+            latitude: point.latitude,
+            longitude: point.longitude,
+            altitude: point.elevation,
+        })
+    }
+}
+
+/// Returns the capture time of a photo in seconds since the Unix epoch,
+/// corrected with the album's clock settings like everywhere else. Photos
+/// from cameras of an unknown time zone are taken to be on UTC.
+pub fn capture_time(exif: &exif::Exif, clock: &Clock) -> Option<i64> {
+    Some(clock.capture_time(exif)?.utc.and_utc().timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GPX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="test" xmlns="http://www.topografix.com/GPX/1/1">
+  <trk><trkseg>
+    <trkpt lat="60.0" lon="24.0"><ele>10</ele><time>2026-02-01T13:00:00Z</time></trkpt>
+    <trkpt lat="60.1" lon="24.2"><ele>20</ele><time>2026-02-01T13:10:00Z</time></trkpt>
+    <trkpt lat="60.2" lon="24.4"><time>2026-02-01T14:00:00+00:00</time></trkpt>
+    <trkpt lat="61.0" lon="25.0"></trkpt>
+  </trkseg></trk>
+</gpx>"#;
+
+    fn time(s: &str) -> i64 {
+        parse_time(s).unwrap()
+    }
+
+    fn track() -> Track {
+        Track {
+            points: parse(GPX),
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parse_track_points() {
+        let points = parse(GPX);
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].elevation, Some(10.0));
+        assert_eq!(points[2].elevation, None);
+        assert_eq!(points[2].time, time("2026-02-01T14:00:00"));
+        assert!(parse("not xml").is_empty());
+    }
+
+    #[test]
+    fn interpolate_between_points() {
+        let track = track();
+        let position = track.position_at(time("2026-02-01T13:05:00Z")).unwrap();
+        assert!((position.latitude - 60.05).abs() < 1e-9);
+        assert!((position.longitude - 24.1).abs() < 1e-9);
+        assert_eq!(position.altitude, Some(15.0));
+
+        let position = track.position_at(time("2026-02-01T13:00:00Z")).unwrap();
+        assert_eq!((position.latitude, position.longitude), (60.0, 24.0));
+    }
+
+    #[test]
+    fn nearest_point_across_gaps_and_ends() {
+        let track = track();
+        // The 50-minute gap is too long to interpolate over.
+        let position = track.position_at(time("2026-02-01T13:14:00Z")).unwrap();
+        assert_eq!(position.latitude, 60.1);
+        let position = track.position_at(time("2026-02-01T13:57:00Z")).unwrap();
+        assert_eq!(position.latitude, 60.2);
+        assert!(track.position_at(time("2026-02-01T13:30:00Z")).is_none());
+
+        let position = track.position_at(time("2026-02-01T12:56:00Z")).unwrap();
+        assert_eq!(position.latitude, 60.0);
+        assert!(track.position_at(time("2026-02-01T12:50:00Z")).is_none());
+        assert!(track.position_at(time("2026-02-01T14:06:00Z")).is_none());
+    }
+
+    #[test]
+    fn capture_time_of_fixture() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/DSCF0199.jpg");
+        let exif = crate::exif::read_exif(&path).unwrap();
+        let taken = time("2026-02-01T15:01:00Z");
+        let captured = capture_time(&exif, &Clock::default()).unwrap();
+        assert_eq!(captured - captured % 60, taken);
+        let clock: Clock = toml::from_str("timezone = \"+02:00\"").unwrap();
+        assert_eq!(capture_time(&exif, &clock), Some(captured - 7200));
+    }
+
+    #[test]
+    fn load_gpx_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("day1.GPX"), GPX).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "").unwrap();
+        let track = Track::load(dir.path()).unwrap();
+        assert_eq!(track.points.len(), 3);
+        assert_eq!(track.files, vec![dir.path().join("day1.GPX")]);
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(Track::load(empty.path()).is_none());
+    }
+}
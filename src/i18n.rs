@@ -0,0 +1,591 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+use serde::{Deserialize, Serialize};
+
+/// Language of the UI strings and dates, set with `language` in `site.toml`.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Fi,
+    Sv,
+    De,
+}
+
+/// Site-wide settings that affect how dates and text are presented.
+#[derive(Default)]
+pub struct Locale {
+    pub language: Language,
+    /// strftime-like format for album timespans, overriding the automatic
+    /// formatting.
+    pub timespan_format: Option<String>,
+}
+
+/// Translated UI strings used by the templates.
+#[derive(Serialize)]
+pub struct Strings {
+    pub albums: &'static str,
+    /// May contain HTML.
+    pub no_albums: &'static str,
+    pub download_all: &'static str,
+    pub previous_photo: &'static str,
+    pub next_photo: &'static str,
+    pub exif: ExifStrings,
+    pub stats: StatsStrings,
+    pub timeline: TimelineStrings,
+    pub map: MapStrings,
+}
+
+/// Strings of the map page.
+#[derive(Serialize)]
+pub struct MapStrings {
+    pub title: &'static str,
+    pub no_photos: &'static str,
+    pub zoom_in: &'static str,
+    pub zoom_out: &'static str,
+}
+
+/// Strings of the timeline pages.
+#[derive(Serialize)]
+pub struct TimelineStrings {
+    pub title: &'static str,
+    pub no_photos: &'static str,
+    pub on_this_day: &'static str,
+    pub on_this_day_empty: &'static str,
+}
+
+/// Headings of the statistics page.
+#[derive(Serialize)]
+pub struct StatsStrings {
+    pub title: &'static str,
+    pub photos: &'static str,
+    pub cameras: &'static str,
+    pub lenses: &'static str,
+    pub focal_lengths: &'static str,
+    pub apertures: &'static str,
+    pub isos: &'static str,
+    pub months: &'static str,
+}
+
+/// Labels and values of the EXIF details panel on the photo page.
+#[derive(Serialize)]
+pub struct ExifStrings {
+    pub details: &'static str,
+    pub date_taken: &'static str,
+    pub location: &'static str,
+    pub camera: &'static str,
+    pub lens: &'static str,
+    pub focal_length: &'static str,
+    pub aperture: &'static str,
+    pub exposure: &'static str,
+    pub iso: &'static str,
+    pub exposure_compensation: &'static str,
+    pub exposure_program: &'static str,
+    pub metering_mode: &'static str,
+    pub flash: &'static str,
+    pub white_balance: &'static str,
+    pub software: &'static str,
+    pub artist: &'static str,
+    pub copyright: &'static str,
+    pub credit: &'static str,
+    pub keywords: &'static str,
+    pub dimensions: &'static str,
+    /// Exposure programs by their EXIF value, 0-8.
+    pub exposure_programs: [&'static str; 9],
+    /// Metering modes by their EXIF value, 0-6, followed by "other".
+    pub metering_modes: [&'static str; 8],
+    pub flash_fired: &'static str,
+    pub flash_not_fired: &'static str,
+    pub white_balance_auto: &'static str,
+    pub white_balance_manual: &'static str,
+    pub film_simulation: &'static str,
+    pub grain_effect: &'static str,
+    pub color_chrome: &'static str,
+    pub color_chrome_blue: &'static str,
+    pub dynamic_range: &'static str,
+    pub off: &'static str,
+    pub weak: &'static str,
+    pub strong: &'static str,
+    pub small: &'static str,
+    pub large: &'static str,
+    pub auto: &'static str,
+}
+
+const EN: Strings = Strings {
+    albums: "Albums",
+    no_albums: "No albums yet. Add a directory with photos to <code>photos/</code> to get started.",
+    download_all: "Download all photos",
+    previous_photo: "Previous photo",
+    next_photo: "Next photo",
+    exif: ExifStrings {
+        details: "Details",
+        date_taken: "Taken",
+        location: "Location",
+        camera: "Camera",
+        lens: "Lens",
+        focal_length: "Focal length",
+        aperture: "Aperture",
+        exposure: "Shutter speed",
+        iso: "ISO",
+        exposure_compensation: "Exposure compensation",
+        exposure_program: "Exposure program",
+        metering_mode: "Metering",
+        flash: "Flash",
+        white_balance: "White balance",
+        software: "Software",
+        artist: "Artist",
+        copyright: "Copyright",
+        credit: "Credit",
+        keywords: "Keywords",
+        dimensions: "Dimensions",
+        exposure_programs: [
+            "Not defined",
+            "Manual",
+            "Program",
+            "Aperture priority",
+            "Shutter priority",
+            "Creative",
+            "Action",
+            "Portrait",
+            "Landscape",
+        ],
+        metering_modes: [
+            "Unknown",
+            "Average",
+            "Center-weighted average",
+            "Spot",
+            "Multi-spot",
+            "Pattern",
+            "Partial",
+            "Other",
+        ],
+        flash_fired: "Fired",
+        flash_not_fired: "Did not fire",
+        white_balance_auto: "Auto",
+        white_balance_manual: "Manual",
+        film_simulation: "Film simulation",
+        grain_effect: "Grain effect",
+        color_chrome: "Color chrome effect",
+        color_chrome_blue: "Color chrome FX blue",
+        dynamic_range: "Dynamic range",
+        off: "Off",
+        weak: "Weak",
+        strong: "Strong",
+        small: "Small",
+        large: "Large",
+        auto: "Auto",
+    },
+    stats: StatsStrings {
+        title: "Statistics",
+        photos: "Photos",
+        cameras: "Cameras",
+        lenses: "Lenses",
+        focal_lengths: "Focal lengths",
+        apertures: "Apertures",
+        isos: "ISO",
+        months: "Photos per month",
+    },
+    timeline: TimelineStrings {
+        title: "Timeline",
+        no_photos: "No photos with a capture date.",
+        on_this_day: "On this day",
+        on_this_day_empty: "No photos were taken on this day in earlier years.",
+    },
+    map: MapStrings {
+        title: "Map",
+        no_photos: "No photos with a location.",
+        zoom_in: "Zoom in",
+        zoom_out: "Zoom out",
+    },
+};
+
+const FI: Strings = Strings {
+    albums: "Albumit",
+    no_albums: "Ei vielä albumeita. Aloita lisäämällä kuvia sisältävä hakemisto hakemistoon <code>photos/</code>.",
+    download_all: "Lataa kaikki kuvat",
+    previous_photo: "Edellinen kuva",
+    next_photo: "Seuraava kuva",
+    exif: ExifStrings {
+        details: "Tiedot",
+        date_taken: "Kuvattu",
+        location: "Sijainti",
+        camera: "Kamera",
+        lens: "Objektiivi",
+        focal_length: "Polttoväli",
+        aperture: "Aukko",
+        exposure: "Suljinaika",
+        iso: "ISO",
+        exposure_compensation: "Valotuksen korjaus",
+        exposure_program: "Valotusohjelma",
+        metering_mode: "Mittaus",
+        flash: "Salama",
+        white_balance: "Valkotasapaino",
+        software: "Ohjelmisto",
+        artist: "Kuvaaja",
+        copyright: "Tekijänoikeus",
+        credit: "Lähde",
+        keywords: "Avainsanat",
+        dimensions: "Koko",
+        exposure_programs: [
+            "Ei määritetty",
+            "Käsisäätö",
+            "Ohjelma",
+            "Aukon esivalinta",
+            "Ajan esivalinta",
+            "Luova",
+            "Toiminta",
+            "Muotokuva",
+            "Maisema",
+        ],
+        metering_modes: [
+            "Tuntematon",
+            "Keskiarvo",
+            "Keskustapainotteinen",
+            "Piste",
+            "Monipiste",
+            "Matriisi",
+            "Osa-alue",
+            "Muu",
+        ],
+        flash_fired: "Välähti",
+        flash_not_fired: "Ei välähtänyt",
+        white_balance_auto: "Automaattinen",
+        white_balance_manual: "Käsisäätö",
+        film_simulation: "Filmisimulaatio",
+        grain_effect: "Raekuvio",
+        color_chrome: "Color chrome -tehoste",
+        color_chrome_blue: "Color chrome FX blue",
+        dynamic_range: "Dynaaminen alue",
+        off: "Pois",
+        weak: "Heikko",
+        strong: "Voimakas",
+        small: "Pieni",
+        large: "Suuri",
+        auto: "Automaattinen",
+    },
+    stats: StatsStrings {
+        title: "Tilastot",
+        photos: "Kuvia",
+        cameras: "Kamerat",
+        lenses: "Objektiivit",
+        focal_lengths: "Polttovälit",
+        apertures: "Aukot",
+        isos: "ISO",
+        months: "Kuvia kuukausittain",
+    },
+    timeline: TimelineStrings {
+        title: "Aikajana",
+        no_photos: "Ei kuvia, joissa on kuvausaika.",
+        on_this_day: "Tänä päivänä",
+        on_this_day_empty: "Tänä päivänä ei ole otettu kuvia aiempina vuosina.",
+    },
+    map: MapStrings {
+        title: "Kartta",
+        no_photos: "Ei kuvia, joissa on sijainti.",
+        zoom_in: "Lähennä",
+        zoom_out: "Loitonna",
+    },
+};
+
+const SV: Strings = Strings {
+    albums: "Album",
+    no_albums: "Inga album ännu. Lägg till en katalog med foton i <code>photos/</code> för att komma igång.",
+    download_all: "Ladda ner alla foton",
+    previous_photo: "Föregående foto",
+    next_photo: "Nästa foto",
+    exif: ExifStrings {
+        details: "Detaljer",
+        date_taken: "Tagen",
+        location: "Plats",
+        camera: "Kamera",
+        lens: "Objektiv",
+        focal_length: "Brännvidd",
+        aperture: "Bländare",
+        exposure: "Slutartid",
+        iso: "ISO",
+        exposure_compensation: "Exponeringskompensation",
+        exposure_program: "Exponeringsprogram",
+        metering_mode: "Ljusmätning",
+        flash: "Blixt",
+        white_balance: "Vitbalans",
+        software: "Programvara",
+        artist: "Fotograf",
+        copyright: "Upphovsrätt",
+        credit: "Källa",
+        keywords: "Nyckelord",
+        dimensions: "Storlek",
+        exposure_programs: [
+            "Ej angivet",
+            "Manuell",
+            "Program",
+            "Bländarprioritet",
+            "Slutartidsprioritet",
+            "Kreativ",
+            "Action",
+            "Porträtt",
+            "Landskap",
+        ],
+        metering_modes: [
+            "Okänd",
+            "Genomsnitt",
+            "Centrumvägt genomsnitt",
+            "Spot",
+            "Multispot",
+            "Matris",
+            "Partiell",
+            "Annan",
+        ],
+        flash_fired: "Utlöst",
+        flash_not_fired: "Ej utlöst",
+        white_balance_auto: "Automatisk",
+        white_balance_manual: "Manuell",
+        film_simulation: "Filmsimulering",
+        grain_effect: "Korneffekt",
+        color_chrome: "Color chrome-effekt",
+        color_chrome_blue: "Color chrome FX blå",
+        dynamic_range: "Dynamiskt omfång",
+        off: "Av",
+        weak: "Svag",
+        strong: "Stark",
+        small: "Liten",
+        large: "Stor",
+        auto: "Automatiskt",
+    },
+    stats: StatsStrings {
+        title: "Statistik",
+        photos: "Foton",
+        cameras: "Kameror",
+        lenses: "Objektiv",
+        focal_lengths: "Brännvidder",
+        apertures: "Bländare",
+        isos: "ISO",
+        months: "Foton per månad",
+    },
+    timeline: TimelineStrings {
+        title: "Tidslinje",
+        no_photos: "Inga foton med tagningsdatum.",
+        on_this_day: "Den här dagen",
+        on_this_day_empty: "Inga foton togs den här dagen tidigare år.",
+    },
+    map: MapStrings {
+        title: "Karta",
+        no_photos: "Inga foton med plats.",
+        zoom_in: "Zooma in",
+        zoom_out: "Zooma ut",
+    },
+};
+
+const DE: Strings = Strings {
+    albums: "Alben",
+    no_albums: "Noch keine Alben. Lege ein Verzeichnis mit Fotos in <code>photos/</code> an, um loszulegen.",
+    download_all: "Alle Fotos herunterladen",
+    previous_photo: "Vorheriges Foto",
+    next_photo: "Nächstes Foto",
+    exif: ExifStrings {
+        details: "Details",
+        date_taken: "Aufgenommen",
+        location: "Ort",
+        camera: "Kamera",
+        lens: "Objektiv",
+        focal_length: "Brennweite",
+        aperture: "Blende",
+        exposure: "Belichtungszeit",
+        iso: "ISO",
+        exposure_compensation: "Belichtungskorrektur",
+        exposure_program: "Belichtungsprogramm",
+        metering_mode: "Belichtungsmessung",
+        flash: "Blitz",
+        white_balance: "Weißabgleich",
+        software: "Software",
+        artist: "Fotograf",
+        copyright: "Copyright",
+        credit: "Bildnachweis",
+        keywords: "Stichwörter",
+        dimensions: "Abmessungen",
+        exposure_programs: [
+            "Nicht definiert",
+            "Manuell",
+            "Programmautomatik",
+            "Zeitautomatik",
+            "Blendenautomatik",
+            "Kreativprogramm",
+            "Actionprogramm",
+            "Porträt",
+            "Landschaft",
+        ],
+        metering_modes: [
+            "Unbekannt",
+            "Integral",
+            "Mittenbetont",
+            "Spot",
+            "Mehrfachspot",
+            "Mehrfeld",
+            "Selektiv",
+            "Andere",
+        ],
+        flash_fired: "Ausgelöst",
+        flash_not_fired: "Nicht ausgelöst",
+        white_balance_auto: "Automatisch",
+        white_balance_manual: "Manuell",
+        film_simulation: "Filmsimulation",
+        grain_effect: "Körnungseffekt",
+        color_chrome: "Color-Chrome-Effekt",
+        color_chrome_blue: "Color Chrome FX Blau",
+        dynamic_range: "Dynamikbereich",
+        off: "Aus",
+        weak: "Schwach",
+        strong: "Stark",
+        small: "Klein",
+        large: "Groß",
+        auto: "Automatisch",
+    },
+    stats: StatsStrings {
+        title: "Statistik",
+        photos: "Fotos",
+        cameras: "Kameras",
+        lenses: "Objektive",
+        focal_lengths: "Brennweiten",
+        apertures: "Blenden",
+        isos: "ISO",
+        months: "Fotos pro Monat",
+    },
+    timeline: TimelineStrings {
+        title: "Zeitleiste",
+        no_photos: "Keine Fotos mit Aufnahmedatum.",
+        on_this_day: "An diesem Tag",
+        on_this_day_empty: "An diesem Tag wurden in früheren Jahren keine Fotos aufgenommen.",
+    },
+    map: MapStrings {
+        title: "Karte",
+        no_photos: "Keine Fotos mit Standort.",
+        zoom_in: "Vergrößern",
+        zoom_out: "Verkleinern",
+    },
+};
+
+impl Language {
+    /// BCP 47 language tag for the `<html lang>` attribute.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Fi => "fi",
+            Language::Sv => "sv",
+            Language::De => "de",
+        }
+    }
+
+    pub fn strings(&self) -> &'static Strings {
+        match self {
+            Language::En => &EN,
+            Language::Fi => &FI,
+            Language::Sv => &SV,
+            Language::De => &DE,
+        }
+    }
+
+    /// Name of the month (1-12) as used on its own or with a year,
+    /// e.g. "June 2024".
+    pub fn month_name(&self, month: u32) -> Option<&'static str> {
+        let names = match self {
+            Language::En => [
+                "January",
+                "February",
+                "March",
+                "April",
+                "May",
+                "June",
+                "July",
+                "August",
+                "September",
+                "October",
+                "November",
+                "December",
+            ],
+            Language::Fi => [
+                "tammikuu",
+                "helmikuu",
+                "maaliskuu",
+                "huhtikuu",
+                "toukokuu",
+                "kesäkuu",
+                "heinäkuu",
+                "elokuu",
+                "syyskuu",
+                "lokakuu",
+                "marraskuu",
+                "joulukuu",
+            ],
+            Language::Sv => [
+                "januari",
+                "februari",
+                "mars",
+                "april",
+                "maj",
+                "juni",
+                "juli",
+                "augusti",
+                "september",
+                "oktober",
+                "november",
+                "december",
+            ],
+            Language::De => [
+                "Januar",
+                "Februar",
+                "März",
+                "April",
+                "Mai",
+                "Juni",
+                "Juli",
+                "August",
+                "September",
+                "Oktober",
+                "November",
+                "Dezember",
+            ],
+        };
+        names.get(month.checked_sub(1)? as usize).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_from_toml() {
+        #[derive(Deserialize)]
+        struct Config {
+            language: Language,
+        }
+        let config: Config = toml::from_str("language = \"fi\"").unwrap();
+        assert_eq!(config.language, Language::Fi);
+        assert!(toml::from_str::<Config>("language = \"xx\"").is_err());
+    }
+
+    #[test]
+    fn month_name_bounds() {
+        assert_eq!(Language::En.month_name(1), Some("January"));
+        assert_eq!(Language::En.month_name(12), Some("December"));
+        assert_eq!(Language::En.month_name(0), None);
+        assert_eq!(Language::En.month_name(13), None);
+    }
+
+    #[test]
+    fn month_name_translations() {
+        assert_eq!(Language::Fi.month_name(6), Some("kesäkuu"));
+        assert_eq!(Language::Sv.month_name(5), Some("maj"));
+        assert_eq!(Language::De.month_name(3), Some("März"));
+    }
+
+    #[test]
+    fn strings_per_language() {
+        assert_eq!(Language::En.strings().albums, "Albums");
+        assert_eq!(Language::Fi.strings().albums, "Albumit");
+        assert_eq!(Language::Sv.strings().albums, "Album");
+        assert_eq!(Language::De.strings().albums, "Alben");
+    }
+}
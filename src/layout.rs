@@ -0,0 +1,111 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+use serde::{Deserialize, Serialize};
+
+/// Width of the content area in pixels (`main` max-width minus padding),
+/// used to decide where the rows break.
+const CONTAINER_WIDTH: f64 = 1136.0;
+/// Gap between photos in pixels (`.photo-row` gap).
+const GAP: f64 = 16.0;
+
+pub const DEFAULT_ROW_HEIGHT: u32 = 280;
+
+/// How the photos of an album are laid out.
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    /// Uniform grid of cards.
+    #[default]
+    Grid,
+    /// Rows that fill the width while keeping each photo's aspect ratio.
+    Justified,
+}
+
+/// Breaks photos with the given aspect ratios (width / height) into rows
+/// that are about `row_height` pixels high. Returns the photo indices of each
+/// row together with their `flex-basis` as a percentage of the row width.
+///
+/// Full rows are scaled to fill the width. The last row keeps the target
+/// height if it's not full, so that a single trailing photo doesn't get
+/// blown up.
+pub fn justify(aspects: &[f64], row_height: f64) -> Vec<Vec<(usize, f64)>> {
+    let mut rows = Vec::new();
+    let mut row: Vec<usize> = Vec::new();
+    let mut row_aspect = 0.0;
+
+    for (i, &aspect) in aspects.iter().enumerate() {
+        row.push(i);
+        row_aspect += aspect;
+        let gaps = GAP * (row.len() - 1) as f64;
+        if row_aspect * row_height + gaps >= CONTAINER_WIDTH {
+            let total = row_aspect;
+            rows.push(
+                row.drain(..)
+                    .map(|j| (j, aspects[j] / total * 100.0))
+                    .collect(),
+            );
+            row_aspect = 0.0;
+        }
+    }
+
+    if !row.is_empty() {
+        rows.push(
+            row.into_iter()
+                .map(|j| (j, aspects[j] * row_height / CONTAINER_WIDTH * 100.0))
+                .collect(),
+        );
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indices(rows: &[Vec<(usize, f64)>]) -> Vec<Vec<usize>> {
+        rows.iter()
+            .map(|row| row.iter().map(|(i, _)| *i).collect())
+            .collect()
+    }
+
+    #[test]
+    fn justify_empty() {
+        assert!(justify(&[], 280.0).is_empty());
+    }
+
+    #[test]
+    fn justify_breaks_rows() {
+        // At 280px, three 3:2 photos are 1260px + gaps wide, so each row
+        // takes three of them.
+        let aspects = [1.5; 7];
+        let rows = justify(&aspects, 280.0);
+        assert_eq!(indices(&rows), vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+    }
+
+    #[test]
+    fn justify_full_rows_fill_width() {
+        let aspects = [1.5, 0.75, 1.5, 2.0];
+        let rows = justify(&aspects, 280.0);
+        let first = &rows[0];
+        let total: f64 = first.iter().map(|(_, basis)| basis).sum();
+        assert!((total - 100.0).abs() < 1e-9);
+        // Widths are proportional to the aspect ratios.
+        assert!((first[0].1 / first[1].1 - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn justify_last_row_keeps_target_height() {
+        let rows = justify(&[1.5], 280.0);
+        assert_eq!(rows.len(), 1);
+        let (_, basis) = rows[0][0];
+        assert!((basis - 1.5 * 280.0 / CONTAINER_WIDTH * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn justify_panorama_gets_own_row() {
+        let rows = justify(&[5.0, 1.5], 280.0);
+        assert_eq!(indices(&rows), vec![vec![0], vec![1]]);
+    }
+}
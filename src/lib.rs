@@ -2,10 +2,31 @@
 //
 // SPDX-License-Identifier: MIT
 
+mod assets;
+mod clock;
+mod download;
 mod exif;
-
+mod formats;
+mod geo;
+mod geocode;
+mod gpx;
+mod i18n;
+mod layout;
+mod makernote;
+mod markdown;
+mod placeholder;
+mod raw;
+mod scrub;
+mod stats;
+mod theme;
+mod timeline;
+mod timespan;
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use askama::Template;
 use axum::Router;
@@ -13,15 +34,21 @@ use axum::extract::{self, State};
 use axum::http::StatusCode;
 use axum::response::{Html, IntoResponse, Response};
 use axum::routing::get;
+use image::DynamicImage;
 use image::imageops::FilterType;
-use serde::Deserialize;
-use tower_http::services::ServeDir;
+use serde::{Deserialize, Serialize};
 
+use assets::{StaticAssets, file_version, fingerprint};
 use exif::{ExifInfo, read_exif_info};
+use i18n::{Language, Locale, Strings};
+use layout::Layout;
+use stats::Stats;
+use theme::{Theme, serialize_exif};
 
 enum AppError {
     Render,
     NotFound,
+    BadRequest,
 }
 
 impl From<askama::Error> for AppError {
@@ -30,6 +57,13 @@ impl From<askama::Error> for AppError {
     }
 }
 
+impl From<minijinja::Error> for AppError {
+    fn from(err: minijinja::Error) -> Self {
+        tracing::error!("failed to render theme template: {:#}", err);
+        AppError::Render
+    }
+}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         match self {
@@ -39,17 +73,60 @@ impl IntoResponse for AppError {
             )
                 .into_response(),
             AppError::NotFound => StatusCode::NOT_FOUND.into_response(),
+            AppError::BadRequest => StatusCode::BAD_REQUEST.into_response(),
         }
     }
 }
 
 const SMALL_SIZE: u32 = 400;
 const MEDIUM_SIZE: u32 = 1200;
+const COVER_WIDTH: u32 = 600;
+const COVER_HEIGHT: u32 = 400;
 
-#[derive(Deserialize)]
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+#[derive(Deserialize, Default)]
 struct SiteConfig {
     title: Option<String>,
     footer_snippet: Option<String>,
+    #[serde(default)]
+    album_order: AlbumOrder,
+    #[serde(default)]
+    language: Language,
+    timespan_format: Option<String>,
+    #[serde(default)]
+    layout: Layout,
+    row_height: Option<u32>,
+    /// URL template of the map tiles, e.g.
+    /// "https://tile.openstreetmap.org/{z}/{x}/{y}.png".
+    map_tiles: Option<String>,
+    /// Attribution shown on the map, may contain HTML.
+    map_attribution: Option<String>,
+}
+
+/// How albums are ordered on the index page. Pinned albums always come
+/// first.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+enum AlbumOrder {
+    Sort(AlbumSort),
+    /// Albums listed by slug come first in the given order, followed by the
+    /// rest sorted newest first.
+    Manual(Vec<String>),
+}
+
+impl Default for AlbumOrder {
+    fn default() -> Self {
+        AlbumOrder::Sort(AlbumSort::Newest)
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum AlbumSort {
+    Newest,
+    Oldest,
+    Title,
 }
 
 #[derive(Clone)]
@@ -58,6 +135,16 @@ struct AppState {
     cache_dir: PathBuf,
     site_title: String,
     footer_snippet: Option<String>,
+    album_order: Arc<AlbumOrder>,
+    locale: Arc<Locale>,
+    layout: Layout,
+    row_height: u32,
+    tiles_dir: PathBuf,
+    map_tiles: String,
+    map_attribution: Option<String>,
+    geocoder: Option<Arc<geocode::Geocoder>>,
+    assets: Arc<StaticAssets>,
+    theme: Option<Arc<Theme>>,
 }
 
 #[derive(Deserialize, Default)]
@@ -65,58 +152,295 @@ struct AlbumMeta {
     title: Option<String>,
     description: Option<String>,
     timespan: Option<String>,
+    #[serde(default)]
+    pinned: bool,
+    cover: Option<String>,
+    #[serde(default)]
+    cover_focus: FocalPoint,
+    /// Offer the album as a ZIP archive at `/album/{slug}/download.zip`.
+    #[serde(default)]
+    download: bool,
+    #[serde(default)]
+    download_size: DownloadSize,
+    /// Overrides the site-wide `layout` and `row_height`.
+    layout: Option<Layout>,
+    row_height: Option<u32>,
+    #[serde(default)]
+    sort: PhotoSort,
+    #[serde(default)]
+    reverse: bool,
+    /// Filenames listed here come first in the given order, followed by the
+    /// rest of the photos in `sort` order.
+    #[serde(default)]
+    order: Vec<String>,
+    #[serde(default)]
+    location: geo::AlbumLocation,
+    /// Corrections to the capture times.
+    #[serde(flatten)]
+    clock: clock::Clock,
+    /// Settings of single photos by filename, `[photos."a.jpg"]`.
+    #[serde(default)]
+    photos: HashMap<String, PhotoMeta>,
 }
 
+/// Title and caption of a photo. They override the IPTC headline and
+/// caption of the photo.
+#[derive(Deserialize, Default, Clone)]
+struct PhotoMeta {
+    title: Option<String>,
+    caption: Option<String>,
+}
+
+/// Point of interest in a photo as fractions of its width and height,
+/// measured from the top left corner.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+struct FocalPoint(f32, f32);
+
+impl Default for FocalPoint {
+    fn default() -> Self {
+        FocalPoint(0.5, 0.5)
+    }
+}
+
+impl FocalPoint {
+    /// Name of the cache directory for cover thumbnails cropped around this
+    /// point, so that changing the focal point invalidates them.
+    fn cache_dir_name(&self) -> String {
+        let percent = |v: f32| (v.clamp(0.0, 1.0) * 100.0).round() as u32;
+        format!("cover-{}-{}", percent(self.0), percent(self.1))
+    }
+}
+
+/// Which version of the photos goes into the album ZIP archive.
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum DownloadSize {
+    #[default]
+    Original,
+    Medium,
+    Small,
+}
+
+/// How photos are ordered within an album.
+#[derive(Deserialize, Default, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum PhotoSort {
+    #[default]
+    Filename,
+    /// EXIF capture time. Photos without one come last, sorted by filename.
+    Date,
+}
+
+#[derive(Serialize)]
 struct Album {
     slug: String,
     title: String,
     description: String,
+    /// `description` rendered from Markdown.
+    description_html: String,
+    /// Long-form description from the album's `README.md`, rendered from
+    /// Markdown.
+    readme_html: String,
     timespan: String,
+    /// Most common place name of the photos, from the reverse geocoder.
+    /// Only set on the album page.
+    location: Option<String>,
     sort_date: Option<String>,
-    cover: Option<String>,
+    cover: Option<Photo>,
+    pinned: bool,
+    download: bool,
 }
 
+#[derive(Clone, Serialize)]
 struct Photo {
     filename: String,
+    /// Fingerprint of the original file, appended to image URLs so that
+    /// they can be cached as immutable.
+    version: String,
+    /// Tiny preview as a data URI, if one has been generated.
+    placeholder: Option<String>,
+    /// Pixel dimensions of the original.
+    dimensions: Option<Dimensions>,
+    /// Pixel dimensions of the medium thumbnail.
+    medium: Option<Dimensions>,
+    /// Whether the photo is an animation; only known on the photo page.
+    animated: bool,
 }
 
-#[derive(Template)]
+#[derive(Clone, Copy, Serialize, Debug, PartialEq)]
+struct Dimensions {
+    width: u32,
+    height: u32,
+}
+
+impl Dimensions {
+    /// Dimensions after scaling to fit within a `max_dim` square, matching
+    /// `DynamicImage::resize`.
+    fn fit(&self, max_dim: u32) -> Dimensions {
+        let ratio = f64::min(
+            f64::from(max_dim) / f64::from(self.width),
+            f64::from(max_dim) / f64::from(self.height),
+        );
+        let scale = |v: u32| ((f64::from(v) * ratio).round() as u32).max(1);
+        Dimensions {
+            width: scale(self.width),
+            height: scale(self.height),
+        }
+    }
+}
+
+#[derive(Template, Serialize)]
 #[template(path = "index.html")]
 struct IndexTemplate {
     site_title: String,
     footer_snippet: Option<String>,
+    lang: Language,
+    t: &'static Strings,
+    #[serde(skip)]
+    assets: Arc<StaticAssets>,
     albums: Vec<Album>,
 }
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
+#[template(path = "stats.html")]
+struct StatsTemplate {
+    site_title: String,
+    footer_snippet: Option<String>,
+    lang: Language,
+    t: &'static Strings,
+    #[serde(skip)]
+    assets: Arc<StaticAssets>,
+    stats: Stats,
+}
+
+#[derive(Template, Serialize)]
+#[template(path = "timeline.html")]
+struct TimelineTemplate {
+    site_title: String,
+    footer_snippet: Option<String>,
+    lang: Language,
+    t: &'static Strings,
+    #[serde(skip)]
+    assets: Arc<StaticAssets>,
+    /// The year or month being shown; `None` on the overview page.
+    heading: Option<String>,
+    years: Vec<timeline::Year>,
+    groups: Vec<timeline::Group>,
+    prev: Option<timeline::Link>,
+    next: Option<timeline::Link>,
+}
+
+#[derive(Template, Serialize)]
+#[template(path = "on_this_day.html")]
+struct OnThisDayTemplate {
+    site_title: String,
+    footer_snippet: Option<String>,
+    lang: Language,
+    t: &'static Strings,
+    #[serde(skip)]
+    assets: Arc<StaticAssets>,
+    /// The day and month being shown, e.g. "18 October".
+    date: String,
+    groups: Vec<timeline::Group>,
+}
+
+#[derive(Deserialize)]
+struct OnThisDayQuery {
+    /// Overrides the current date, e.g. "2026-10-18".
+    date: Option<String>,
+}
+
+#[derive(Serialize)]
+struct OnThisDayJson {
+    date: String,
+    years: Vec<OnThisDayYear>,
+}
+
+#[derive(Serialize)]
+struct OnThisDayYear {
+    year: i32,
+    photos: Vec<OnThisDayPhoto>,
+}
+
+#[derive(Serialize)]
+struct OnThisDayPhoto {
+    album: String,
+    filename: String,
+    taken: String,
+    url: String,
+    thumbnail: String,
+}
+
+#[derive(Template, Serialize)]
+#[template(path = "map.html")]
+struct MapTemplate {
+    site_title: String,
+    footer_snippet: Option<String>,
+    lang: Language,
+    t: &'static Strings,
+    #[serde(skip)]
+    assets: Arc<StaticAssets>,
+    /// URL template of the map tiles with `{z}`, `{x}` and `{y}`.
+    tiles: String,
+    attribution: Option<String>,
+}
+
+#[derive(Template, Serialize)]
 #[template(path = "album.html")]
 struct AlbumTemplate {
     site_title: String,
     footer_snippet: Option<String>,
+    lang: Language,
+    t: &'static Strings,
+    #[serde(skip)]
+    assets: Arc<StaticAssets>,
     album: Album,
     photos: Vec<Photo>,
+    /// Set when the album uses the justified layout.
+    rows: Option<Vec<PhotoRow>>,
+}
+
+#[derive(Serialize)]
+struct PhotoRow {
+    items: Vec<RowItem>,
+}
+
+#[derive(Serialize)]
+struct RowItem {
+    photo: Photo,
+    /// `flex-basis` as a percentage of the row width.
+    basis: f64,
 }
 
-#[derive(Template)]
+#[derive(Template, Serialize)]
 #[template(path = "photo.html")]
 struct PhotoTemplate {
     site_title: String,
     footer_snippet: Option<String>,
+    lang: Language,
+    t: &'static Strings,
+    #[serde(skip)]
+    assets: Arc<StaticAssets>,
     album: Album,
     photo: Photo,
+    /// From `album.toml` or the IPTC headline.
+    title: Option<String>,
+    /// From `album.toml` or the IPTC caption.
+    caption: Option<String>,
     prev: Option<Photo>,
     next: Option<Photo>,
+    #[serde(serialize_with = "serialize_exif")]
     exif: ExifInfo,
+    /// The labelled EXIF, IPTC and MakerNote fields shown in the details
+    /// panel, from `ExifInfo::details`.
+    details: Vec<(&'static str, String)>,
 }
 
 fn load_site_config(data_dir: &Path) -> SiteConfig {
     std::fs::read_to_string(data_dir.join("site.toml"))
         .ok()
         .and_then(|s| toml::from_str(&s).ok())
-        .unwrap_or(SiteConfig {
-            title: None,
-            footer_snippet: None,
-        })
+        .unwrap_or_default()
 }
 
 /// Validates that a user-supplied path segment is a plain filename with no
@@ -125,38 +449,401 @@ fn is_safe_path_segment(segment: &str) -> bool {
     Path::new(segment).file_name() == Some(OsStr::new(segment))
 }
 
+/// Renders a page with the theme's template if there is one, falling back to
+/// the built-in template.
+fn render_page<T: Template + Serialize>(
+    state: &AppState,
+    name: &str,
+    page: &T,
+) -> Result<String, AppError> {
+    if let Some(rendered) = state.theme.as_ref().and_then(|t| t.render(name, page)) {
+        return Ok(rendered?);
+    }
+    Ok(page.render()?)
+}
+
 pub fn build_router(data_dir: &Path, cache_dir: &Path) -> Router {
     let config = load_site_config(data_dir);
     let photos_dir = data_dir.join("photos");
+    let theme_dir = data_dir.join("theme");
+    let assets = Arc::new(StaticAssets::load(&theme_dir.join("static")));
+    let theme = Theme::load(&theme_dir.join("templates"), assets.clone()).map(Arc::new);
+    let geocoder = geocode::Geocoder::load(&data_dir.join("geonames")).map(Arc::new);
+    if let Some(geocoder) = &geocoder {
+        tracing::info!(
+            "loaded {} places for reverse geocoding",
+            geocoder.city_count()
+        );
+    }
     let state = AppState {
         photos_dir,
         cache_dir: cache_dir.to_path_buf(),
         site_title: config.title.unwrap_or_else(|| "Kuvasivu".to_string()),
         footer_snippet: config.footer_snippet,
+        album_order: Arc::new(config.album_order),
+        locale: Arc::new(Locale {
+            language: config.language,
+            timespan_format: config.timespan_format,
+        }),
+        layout: config.layout,
+        row_height: config.row_height.unwrap_or(layout::DEFAULT_ROW_HEIGHT),
+        tiles_dir: data_dir.join("tiles"),
+        map_tiles: config
+            .map_tiles
+            .unwrap_or_else(|| geo::LOCAL_TILES.to_string()),
+        map_attribution: config.map_attribution,
+        geocoder,
+        assets,
+        theme,
     };
 
     Router::new()
         .route("/", get(index))
+        .route("/stats", get(stats))
+        .route("/timeline", get(timeline))
+        .route("/timeline/{year}", get(timeline_year))
+        .route("/timeline/{year}/{month}", get(timeline_month))
+        .route("/on-this-day", get(on_this_day))
+        .route("/on-this-day.json", get(on_this_day_json))
+        .route("/map", get(map))
+        .route("/api/geo.geojson", get(geojson))
         .route("/album/{slug}", get(album))
+        .route("/album/{slug}/download.zip", get(download_album))
         .route("/album/{slug}/{filename}", get(photo))
         .route("/photos/{album}/{filename}", get(serve_photo))
         .route("/thumbs/{album}/{size}/{filename}", get(serve_thumb))
-        .nest_service("/static", ServeDir::new("static"))
+        .route("/static/{filename}", get(serve_static))
+        .route("/tiles/{z}/{x}/{y}", get(serve_tile))
         .with_state(state)
 }
 
 async fn index(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
-    let albums = scan_albums(&state.photos_dir);
+    let mut albums = scan_albums(&state.photos_dir, &state.album_order, &state.locale);
+    for album in &mut albums {
+        if let Some(cover) = &mut album.cover {
+            let focus = load_meta(&state.photos_dir.join(&album.slug)).cover_focus;
+            let dir_name = focus.cache_dir_name();
+            let variant = placeholder::Variant::Cover(&dir_name);
+            load_placeholders(&state, &album.slug, variant, std::slice::from_mut(cover));
+        }
+    }
     let site_title = state.site_title.to_string();
     let footer_snippet = state.footer_snippet.clone();
-    Ok(Html(
-        (IndexTemplate {
-            site_title,
-            footer_snippet,
-            albums,
+    let page = IndexTemplate {
+        site_title,
+        footer_snippet,
+        lang: state.locale.language,
+        t: state.locale.language.strings(),
+        assets: state.assets.clone(),
+        albums,
+    };
+    Ok(Html(render_page(&state, "index.html", &page)?))
+}
+
+async fn stats(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
+    let photos: Vec<PathBuf> = album_dirs(&state.photos_dir)
+        .into_iter()
+        .flat_map(|(_, path)| {
+            list_photos(&path)
+                .into_iter()
+                .map(move |photo| path.join(photo.filename))
         })
-        .render()?,
-    ))
+        .collect();
+    let stats = stats::collect(&photos, state.locale.language);
+
+    let site_title = state.site_title.to_string();
+    let footer_snippet = state.footer_snippet.clone();
+    let page = StatsTemplate {
+        site_title,
+        footer_snippet,
+        lang: state.locale.language,
+        t: state.locale.language.strings(),
+        assets: state.assets.clone(),
+        stats,
+    };
+    Ok(Html(render_page(&state, "stats.html", &page)?))
+}
+
+/// Returns every photo with a capture date, in chronological order.
+fn timeline_entries(photos_dir: &Path) -> Vec<timeline::Entry> {
+    let mut entries = Vec::new();
+    for (slug, path) in album_dirs(photos_dir) {
+        let clock = load_meta(&path).clock;
+        for photo in list_photos(&path) {
+            let Some(taken) = clock.read(&path.join(&photo.filename)) else {
+                continue;
+            };
+            let taken = taken.to_exif_string();
+            let Some(date) = timespan::Date::parse(&taken) else {
+                continue;
+            };
+            entries.push(timeline::Entry {
+                album: slug.clone(),
+                photo,
+                taken,
+                date,
+            });
+        }
+    }
+    timeline::sort(&mut entries);
+    entries
+}
+
+/// Fills in the dimensions and placeholders of the photos that are shown.
+fn load_timeline_photos(state: &AppState, groups: &mut [timeline::Group]) {
+    for item in groups.iter_mut().flat_map(|g| g.photos.iter_mut()) {
+        let photos = std::slice::from_mut(&mut item.photo);
+        load_dimensions(&state.photos_dir.join(&item.album), photos);
+        load_placeholders(state, &item.album, placeholder::Variant::Photo, photos);
+    }
+}
+
+fn render_timeline(
+    state: &AppState,
+    heading: Option<String>,
+    years: Vec<timeline::Year>,
+    mut groups: Vec<timeline::Group>,
+    (prev, next): (Option<timeline::Link>, Option<timeline::Link>),
+) -> Result<Html<String>, AppError> {
+    load_timeline_photos(state, &mut groups);
+    let site_title = state.site_title.to_string();
+    let footer_snippet = state.footer_snippet.clone();
+    let page = TimelineTemplate {
+        site_title,
+        footer_snippet,
+        lang: state.locale.language,
+        t: state.locale.language.strings(),
+        assets: state.assets.clone(),
+        heading,
+        years,
+        groups,
+        prev,
+        next,
+    };
+    Ok(Html(render_page(state, "timeline.html", &page)?))
+}
+
+async fn timeline(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
+    let entries = timeline_entries(&state.photos_dir);
+    let years = timeline::navigation(&entries, None, state.locale.language);
+    render_timeline(&state, None, years, Vec::new(), (None, None))
+}
+
+async fn timeline_year(
+    State(state): State<AppState>,
+    extract::Path(year): extract::Path<i32>,
+) -> Result<impl IntoResponse, AppError> {
+    let lang = state.locale.language;
+    let entries = timeline_entries(&state.photos_dir);
+    let years = timeline::navigation(&entries, Some((year, None)), lang);
+    let adjacent = timeline::adjacent_years(&entries, year);
+    let entries: Vec<_> = entries
+        .into_iter()
+        .filter(|e| e.date.year == year)
+        .collect();
+    if entries.is_empty() {
+        return Err(AppError::NotFound);
+    }
+    let groups = timeline::by_month(entries, lang);
+    render_timeline(&state, Some(year.to_string()), years, groups, adjacent)
+}
+
+async fn timeline_month(
+    State(state): State<AppState>,
+    extract::Path((year, month)): extract::Path<(i32, u32)>,
+) -> Result<impl IntoResponse, AppError> {
+    let lang = state.locale.language;
+    let entries = timeline_entries(&state.photos_dir);
+    let years = timeline::navigation(&entries, Some((year, Some(month))), lang);
+    let adjacent = timeline::adjacent_months(&entries, year, month, lang);
+    let entries: Vec<_> = entries
+        .into_iter()
+        .filter(|e| (e.date.year, e.date.month) == (year, month))
+        .collect();
+    if entries.is_empty() {
+        return Err(AppError::NotFound);
+    }
+    let heading = exif::format_year_month(&format!("{}-{:02}", year, month), lang);
+    let groups = timeline::by_day(entries, lang);
+    render_timeline(&state, Some(heading), years, groups, adjacent)
+}
+
+/// Returns the date to show and the photos taken on the same day in
+/// earlier years, in chronological order.
+fn on_this_day_entries(
+    photos_dir: &Path,
+    query: &OnThisDayQuery,
+) -> Result<(timespan::Date, Vec<timeline::Entry>), AppError> {
+    let today = match &query.date {
+        Some(date) => timespan::Date::parse(date).ok_or(AppError::BadRequest)?,
+        None => {
+            use chrono::Datelike;
+            let now = chrono::Local::now().date_naive();
+            timespan::Date {
+                year: now.year(),
+                month: now.month(),
+                day: now.day(),
+            }
+        }
+    };
+    let entries = timeline_entries(photos_dir)
+        .into_iter()
+        .filter(|e| timeline::is_on_this_day(e.date, today))
+        .collect();
+    Ok((today, entries))
+}
+
+async fn on_this_day(
+    State(state): State<AppState>,
+    extract::Query(query): extract::Query<OnThisDayQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let (today, entries) = on_this_day_entries(&state.photos_dir, &query)?;
+    let mut groups = timeline::by_year(entries);
+    load_timeline_photos(&state, &mut groups);
+
+    let site_title = state.site_title.to_string();
+    let footer_snippet = state.footer_snippet.clone();
+    let page = OnThisDayTemplate {
+        site_title,
+        footer_snippet,
+        lang: state.locale.language,
+        t: state.locale.language.strings(),
+        assets: state.assets.clone(),
+        date: timespan::day_month(state.locale.language, today),
+        groups,
+    };
+    Ok(Html(render_page(&state, "on_this_day.html", &page)?))
+}
+
+async fn on_this_day_json(
+    State(state): State<AppState>,
+    extract::Query(query): extract::Query<OnThisDayQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let (today, entries) = on_this_day_entries(&state.photos_dir, &query)?;
+    let mut years: Vec<OnThisDayYear> = Vec::new();
+    for entry in entries {
+        if years.last().is_none_or(|y| y.year != entry.date.year) {
+            years.push(OnThisDayYear {
+                year: entry.date.year,
+                photos: Vec::new(),
+            });
+        }
+        let album = entry.album;
+        let photo = entry.photo;
+        years.last_mut().unwrap().photos.push(OnThisDayPhoto {
+            url: format!("/album/{}/{}", album, photo.filename),
+            thumbnail: format!(
+                "/thumbs/{}/medium/{}?v={}",
+                album, photo.filename, photo.version
+            ),
+            album,
+            filename: photo.filename,
+            taken: entry.taken,
+        });
+    }
+    years.reverse();
+    Ok(axum::Json(OnThisDayJson {
+        date: format!("{:04}-{:02}-{:02}", today.year, today.month, today.day),
+        years,
+    }))
+}
+
+async fn map(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
+    let site_title = state.site_title.to_string();
+    let footer_snippet = state.footer_snippet.clone();
+    let page = MapTemplate {
+        site_title,
+        footer_snippet,
+        lang: state.locale.language,
+        t: state.locale.language.strings(),
+        assets: state.assets.clone(),
+        tiles: state.map_tiles.clone(),
+        attribution: state.map_attribution.clone(),
+    };
+    Ok(Html(render_page(&state, "map.html", &page)?))
+}
+
+/// Returns the place name of a photo if there's a reverse geocoder. The name
+/// is cached until the photo, the album settings or the GPX tracks change.
+fn photo_place(
+    state: &AppState,
+    slug: &str,
+    album_path: &Path,
+    locator: &geo::Locator,
+    filename: &str,
+) -> Option<String> {
+    let geocoder = state.geocoder.as_ref()?;
+    if locator.is_hidden() {
+        return None;
+    }
+    let original = album_path.join(filename);
+    let mut sources = vec![original.clone(), album_path.join("album.toml")];
+    sources.extend_from_slice(locator.files());
+    geocoder.cached_place(&state.cache_dir, slug, filename, &sources, || {
+        locator.position(&original)
+    })
+}
+
+/// Returns the most common place name of the photos in an album.
+fn album_place(
+    state: &AppState,
+    slug: &str,
+    album_path: &Path,
+    locator: &geo::Locator,
+    photos: &[Photo],
+) -> Option<String> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for photo in photos {
+        let Some(place) = photo_place(state, slug, album_path, locator, &photo.filename) else {
+            continue;
+        };
+        match counts.iter_mut().find(|(p, _)| *p == place) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((place, 1)),
+        }
+    }
+    // The first place wins ties, as `max_by_key` would pick the last one.
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(place, _)| place)
+}
+
+/// Serves the locations of the photos as GeoJSON. Albums with
+/// `location = false` are left out.
+async fn geojson(State(state): State<AppState>) -> impl IntoResponse {
+    let mut features = Vec::new();
+    for (slug, path) in album_dirs(&state.photos_dir) {
+        let meta = load_meta(&path);
+        let locator = geo::Locator::new(&path, meta.location, meta.clock);
+        if locator.is_hidden() {
+            continue;
+        }
+        let album_title = meta.title.unwrap_or_else(|| slug_to_title(&slug));
+        for photo in list_photos(&path) {
+            let Some(position) = locator.position(&path.join(&photo.filename)) else {
+                continue;
+            };
+            features.push(geo::Feature::new(
+                position,
+                geo::PhotoProperties {
+                    album: slug.clone(),
+                    album_title: album_title.clone(),
+                    url: format!("/album/{}/{}", slug, photo.filename),
+                    thumbnail: format!(
+                        "/thumbs/{}/small/{}?v={}",
+                        slug, photo.filename, photo.version
+                    ),
+                    filename: photo.filename,
+                },
+            ));
+        }
+    }
+    (
+        [(axum::http::header::CONTENT_TYPE, "application/geo+json")],
+        axum::Json(geo::FeatureCollection::new(features)),
+    )
 }
 
 async fn album(
@@ -171,20 +858,35 @@ async fn album(
         return Err(AppError::NotFound);
     }
 
-    let photos = list_photos(&album_path);
-    let album = load_album(&slug, &album_path, &photos);
+    let mut photos = list_photos(&album_path);
+    let mut album = load_album(&slug, &album_path, &photos, &state.locale);
+    load_placeholders(&state, &slug, placeholder::Variant::Photo, &mut photos);
+    load_dimensions(&album_path, &mut photos);
+
+    let meta = load_meta(&album_path);
+    let locator = geo::Locator::new(&album_path, meta.location, meta.clock);
+    album.location = album_place(&state, &slug, &album_path, &locator, &photos);
+    let rows = match meta.layout.unwrap_or(state.layout) {
+        Layout::Grid => None,
+        Layout::Justified => {
+            let row_height = meta.row_height.unwrap_or(state.row_height);
+            Some(justify_photos(&photos, row_height))
+        }
+    };
 
     let site_title = state.site_title.to_string();
     let footer_snippet = state.footer_snippet.clone();
-    Ok(Html(
-        (AlbumTemplate {
-            site_title,
-            footer_snippet,
-            album,
-            photos,
-        })
-        .render()?,
-    ))
+    let page = AlbumTemplate {
+        site_title,
+        footer_snippet,
+        lang: state.locale.language,
+        t: state.locale.language.strings(),
+        assets: state.assets.clone(),
+        album,
+        photos,
+        rows,
+    };
+    Ok(Html(render_page(&state, "album.html", &page)?))
 }
 
 async fn photo(
@@ -199,7 +901,8 @@ async fn photo(
         return Err(AppError::NotFound);
     }
 
-    let photos = list_photos(&album_path);
+    let mut photos = list_photos(&album_path);
+    load_dimensions(&album_path, &mut photos);
 
     let index = photos
         .iter()
@@ -207,182 +910,578 @@ async fn photo(
         .ok_or(AppError::NotFound)?;
 
     let prev = if index > 0 {
-        Some(Photo {
-            filename: photos[index - 1].filename.clone(),
-        })
+        Some(photos[index - 1].clone())
     } else {
         None
     };
 
-    let next = if index + 1 < photos.len() {
-        Some(Photo {
-            filename: photos[index + 1].filename.clone(),
-        })
-    } else {
-        None
-    };
+    let next = photos.get(index + 1).cloned();
 
-    let album = load_album(&slug, &album_path, &photos);
+    let album = load_album(&slug, &album_path, &photos, &state.locale);
 
     let photo_path = album_path.join(&filename);
-    let exif = read_exif_info(&photo_path);
+    let mut exif = read_exif_info(&photo_path, state.locale.language);
+    let iptc = exif::read_iptc(&photo_path);
+    exif.add_iptc(&iptc);
+    let meta = load_meta(&album_path);
+    let photo_meta = meta.photos.get(&filename).cloned().unwrap_or_default();
+    let locator = geo::Locator::new(&album_path, meta.location, meta.clock);
+    exif.location = photo_place(&state, &slug, &album_path, &locator, &filename);
 
-    let photo = Photo {
-        filename: filename.clone(),
-    };
+    let mut photo = photos[index].clone();
+    photo.animated = formats::is_animated(&photo_path);
 
     let site_title = state.site_title.to_string();
     let footer_snippet = state.footer_snippet.clone();
-    Ok(Html(
-        (PhotoTemplate {
-            site_title,
-            footer_snippet,
-            album,
-            photo,
-            prev,
-            next,
-            exif,
+    let page = PhotoTemplate {
+        site_title,
+        footer_snippet,
+        lang: state.locale.language,
+        t: state.locale.language.strings(),
+        assets: state.assets.clone(),
+        album,
+        photo,
+        title: photo_meta.title.or(iptc.headline),
+        caption: photo_meta.caption.or(iptc.caption),
+        prev,
+        next,
+        details: exif.details(state.locale.language.strings()),
+        exif,
+    };
+    Ok(Html(render_page(&state, "photo.html", &page)?))
+}
+
+async fn download_album(
+    State(state): State<AppState>,
+    extract::Path(slug): extract::Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !is_safe_path_segment(&slug) {
+        return Err(AppError::NotFound);
+    }
+    let album_path = state.photos_dir.join(&slug);
+    if !album_path.is_dir() {
+        return Err(AppError::NotFound);
+    }
+    let meta = load_meta(&album_path);
+    if !meta.download {
+        return Err(AppError::NotFound);
+    }
+
+    let names = list_photos(&album_path)
+        .into_iter()
+        .map(|p| p.filename)
+        .collect();
+    let size = meta.download_size;
+    let hidden = meta.location == geo::AlbumLocation::Gps(false);
+    let album = slug.clone();
+    let body = download::stream_zip(names, move |filename| {
+        // The entry gets the extension of the file that is streamed: RAW
+        // files are offered as their JPEG previews, and the thumbnails of
+        // some formats are stored as JPEG or PNG.
+        let streamed = if size == DownloadSize::Original && !raw::is_raw(Path::new(filename)) {
+            filename.to_string()
+        } else {
+            formats::thumbnail_name(filename)
+        };
+        let stem = Path::new(filename).file_stem().unwrap_or_default();
+        let entry_name = match Path::new(&streamed).extension() {
+            Some(extension) => {
+                format!("{}.{}", stem.to_string_lossy(), extension.to_string_lossy())
+            }
+            None => streamed,
+        };
+        let path = match size {
+            DownloadSize::Original => {
+                original_path(&state.cache_dir, &album, &album_path, filename, hidden)
+            }
+            DownloadSize::Medium => ensure_thumbnail(
+                &state.photos_dir,
+                &state.cache_dir,
+                &album,
+                "medium",
+                filename,
+            ),
+            DownloadSize::Small => ensure_thumbnail(
+                &state.photos_dir,
+                &state.cache_dir,
+                &album,
+                "small",
+                filename,
+            ),
+        };
+        let path = path.map_err(|status| std::io::Error::other(status.to_string()))?;
+        Ok((entry_name, path))
+    });
+
+    // Keep the header value plain ASCII regardless of the directory name.
+    let archive_name: String = slug
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
         })
-        .render()?,
+        .collect();
+    Ok((
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                "application/zip".to_string(),
+            ),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.zip\"", archive_name),
+            ),
+        ],
+        body,
     ))
 }
 
+#[derive(Deserialize)]
+struct VersionQuery {
+    /// The `file_version` of the photo the URL was made for.
+    v: Option<String>,
+}
+
+/// Only a URL carrying the current version of the photo is safe to cache
+/// forever.
+fn photo_cache_control(original: &Path, query: &VersionQuery) -> &'static str {
+    match &query.v {
+        Some(v) if *v == file_version(original) => IMMUTABLE,
+        _ => "no-cache",
+    }
+}
+
 async fn serve_photo(
     State(state): State<AppState>,
     extract::Path((album, filename)): extract::Path<(String, String)>,
+    extract::Query(query): extract::Query<VersionQuery>,
 ) -> Result<impl IntoResponse, StatusCode> {
     if !is_safe_path_segment(&album) || !is_safe_path_segment(&filename) {
         return Err(StatusCode::NOT_FOUND);
     }
-    let path = state.photos_dir.join(&album).join(&filename);
-    serve_file(&path).await
+    let album_path = state.photos_dir.join(&album);
+    let hidden = load_meta(&album_path).location == geo::AlbumLocation::Gps(false);
+    let path = original_path(&state.cache_dir, &album, &album_path, &filename, hidden)?;
+    let cache_control = photo_cache_control(&album_path.join(&filename), &query);
+    serve_file(&path, cache_control).await
 }
 
 async fn serve_thumb(
     State(state): State<AppState>,
     extract::Path((album, size, filename)): extract::Path<(String, String, String)>,
+    extract::Query(query): extract::Query<VersionQuery>,
 ) -> Result<impl IntoResponse, StatusCode> {
     if !is_safe_path_segment(&album) || !is_safe_path_segment(&filename) {
         return Err(StatusCode::NOT_FOUND);
     }
-    let max_dim = match size.as_str() {
-        "small" => SMALL_SIZE,
-        "medium" => MEDIUM_SIZE,
+    let thumb_path = ensure_thumbnail(
+        &state.photos_dir,
+        &state.cache_dir,
+        &album,
+        &size,
+        &filename,
+    )?;
+    let original = state.photos_dir.join(&album).join(&filename);
+    serve_file(&thumb_path, photo_cache_control(&original, &query)).await
+}
+
+/// Returns the path of the cached thumbnail, generating it first if needed.
+fn ensure_thumbnail(
+    photos_dir: &Path,
+    cache_dir: &Path,
+    album: &str,
+    size: &str,
+    filename: &str,
+) -> Result<PathBuf, StatusCode> {
+    let album_path = photos_dir.join(album);
+    let (kind, dir_name) = match size {
+        "small" => (Thumbnail::Fit(SMALL_SIZE), size.to_string()),
+        "medium" => (Thumbnail::Fit(MEDIUM_SIZE), size.to_string()),
+        "cover" => {
+            let focus = load_meta(&album_path).cover_focus;
+            (Thumbnail::Cover(focus), focus.cache_dir_name())
+        }
         _ => return Err(StatusCode::BAD_REQUEST),
     };
 
-    let album_path = state.photos_dir.join(&album);
-    let original = album_path.join(&filename);
+    let original = album_path.join(filename);
     if !original.is_file() {
         return Err(StatusCode::NOT_FOUND);
     }
 
-    let thumb_dir = state.cache_dir.join(&album).join(&size);
-    let thumb_path = thumb_dir.join(&filename);
+    let thumb_dir = cache_dir.join(album).join(&dir_name);
+    let thumb_path = thumb_dir.join(formats::thumbnail_name(filename));
+
+    if !thumb_path.is_file() || is_stale(&original, &thumb_path) {
+        // Cover thumbnails are cropped, so they get placeholders of their own.
+        let variant = match kind {
+            Thumbnail::Fit(_) => placeholder::Variant::Photo,
+            Thumbnail::Cover(_) => placeholder::Variant::Cover(&dir_name),
+        };
+        let source = source_path(cache_dir, album, &album_path, filename)?;
+        let thumb = generate_thumbnail(&source, &thumb_path, &thumb_dir, kind)?;
+        placeholder::store(cache_dir, album, variant, filename, &original, &thumb);
+    }
+    Ok(thumb_path)
+}
+
+/// Returns the path of the file to hand out for a photo: the image from
+/// `source_path`, without its GPS position if the album is kept off the
+/// map.
+fn original_path(
+    cache_dir: &Path,
+    album: &str,
+    album_path: &Path,
+    filename: &str,
+    hidden: bool,
+) -> Result<PathBuf, StatusCode> {
+    let source = source_path(cache_dir, album, album_path, filename)?;
+    if !hidden {
+        return Ok(source);
+    }
+    scrub::ensure_scrubbed(cache_dir, album, &source).map_err(|err| {
+        tracing::warn!("can't scrub {}: {}", source.display(), err);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
 
-    if !thumb_path.is_file() {
-        generate_thumbnail(&original, &thumb_path, &thumb_dir, max_dim)?;
+/// Returns the path of the image to show for a photo: the file itself, or
+/// the embedded preview of a RAW file.
+fn source_path(
+    cache_dir: &Path,
+    album: &str,
+    album_path: &Path,
+    filename: &str,
+) -> Result<PathBuf, StatusCode> {
+    let original = album_path.join(filename);
+    if !original.is_file() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    if !raw::is_raw(&original) {
+        return Ok(original);
     }
+    raw::ensure_preview(cache_dir, album, &original, filename).map_err(|err| {
+        tracing::warn!("no preview in {}: {}", original.display(), err);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+fn justify_photos(photos: &[Photo], row_height: u32) -> Vec<PhotoRow> {
+    // Photos of unknown size are laid out as 3:2.
+    let aspects: Vec<f64> = photos
+        .iter()
+        .map(|p| {
+            p.dimensions
+                .map_or(1.5, |d| f64::from(d.width) / f64::from(d.height))
+        })
+        .collect();
+    layout::justify(&aspects, f64::from(row_height))
+        .into_iter()
+        .map(|row| PhotoRow {
+            items: row
+                .into_iter()
+                .map(|(i, basis)| RowItem {
+                    photo: photos[i].clone(),
+                    basis,
+                })
+                .collect(),
+        })
+        .collect()
+}
 
-    serve_file(&thumb_path).await
+/// Fills in the pixel dimensions of the photos by reading the image headers.
+fn load_dimensions(album_path: &Path, photos: &mut [Photo]) {
+    for photo in photos {
+        let path = album_path.join(&photo.filename);
+        let dimensions = if raw::is_raw(&path) {
+            raw::dimensions(&path)
+        } else {
+            image::image_dimensions(&path).ok()
+        };
+        photo.dimensions = dimensions
+            .filter(|&(width, height)| width > 0 && height > 0)
+            .map(|(width, height)| Dimensions { width, height });
+        photo.medium = photo.dimensions.map(|d| d.fit(MEDIUM_SIZE));
+    }
+}
+
+/// Fills in the cached placeholders of the photos.
+fn load_placeholders(
+    state: &AppState,
+    album: &str,
+    variant: placeholder::Variant,
+    photos: &mut [Photo],
+) {
+    let album_path = state.photos_dir.join(album);
+    for photo in photos {
+        let original = album_path.join(&photo.filename);
+        photo.placeholder =
+            placeholder::load(&state.cache_dir, album, variant, &photo.filename, &original);
+    }
+}
+
+/// Returns true if the original has been modified after the thumbnail was
+/// generated.
+fn is_stale(original: &Path, thumb_path: &Path) -> bool {
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(original), modified(thumb_path)) {
+        (Some(original), Some(thumb)) => original > thumb,
+        _ => false,
+    }
+}
+
+enum Thumbnail {
+    /// Scaled to fit within a square of the given size.
+    Fit(u32),
+    /// Cropped to the album card aspect ratio around the focal point.
+    Cover(FocalPoint),
 }
 
 fn generate_thumbnail(
     original: &Path,
     thumb_path: &Path,
     thumb_dir: &Path,
-    max_dim: u32,
-) -> Result<(), StatusCode> {
+    kind: Thumbnail,
+) -> Result<DynamicImage, StatusCode> {
     std::fs::create_dir_all(thumb_dir).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     let img = image::open(original).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let thumb = img.resize(max_dim, max_dim, FilterType::Lanczos3);
+    let thumb = match kind {
+        Thumbnail::Fit(max_dim) => img.resize(max_dim, max_dim, FilterType::Lanczos3),
+        Thumbnail::Cover(focus) => {
+            let (x, y, w, h) = crop_around(img.width(), img.height(), focus);
+            img.crop_imm(x, y, w, h)
+                .resize_exact(COVER_WIDTH, COVER_HEIGHT, FilterType::Lanczos3)
+        }
+    };
+    let thumb = formats::to_storable(thumb, thumb_path);
     thumb
         .save(thumb_path)
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    Ok(())
+    Ok(thumb)
 }
 
-async fn serve_file(path: &Path) -> Result<impl IntoResponse + use<>, StatusCode> {
-    if !path.is_file() {
+/// Returns the largest `(x, y, width, height)` region with the cover aspect
+/// ratio that is centered on the focal point as closely as the image bounds
+/// allow.
+fn crop_around(width: u32, height: u32, focus: FocalPoint) -> (u32, u32, u32, u32) {
+    let (crop_w, crop_h) = if width * COVER_HEIGHT > height * COVER_WIDTH {
+        (height * COVER_WIDTH / COVER_HEIGHT, height)
+    } else {
+        (width, (width * COVER_HEIGHT / COVER_WIDTH).max(1))
+    };
+    let offset = |size: u32, crop: u32, focus: f32| {
+        let center = (size as f32 * focus.clamp(0.0, 1.0)).round() as u32;
+        center.saturating_sub(crop / 2).min(size - crop)
+    };
+    (
+        offset(width, crop_w, focus.0),
+        offset(height, crop_h, focus.1),
+        crop_w,
+        crop_h,
+    )
+}
+
+async fn serve_static(
+    State(state): State<AppState>,
+    extract::Path(filename): extract::Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (name, asset, current) = state
+        .assets
+        .resolve(&filename)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Only a URL carrying the current fingerprint is safe to cache forever.
+    let cache_control = if current { IMMUTABLE } else { "no-cache" };
+
+    Ok((
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                content_type(Path::new(name)),
+            ),
+            (axum::http::header::CACHE_CONTROL, cache_control),
+        ],
+        asset.body.clone(),
+    ))
+}
+
+/// Serves map tiles from the `tiles` directory of the data directory, laid
+/// out as `tiles/{z}/{x}/{y}.png`.
+async fn serve_tile(
+    State(state): State<AppState>,
+    extract::Path((z, x, y)): extract::Path<(String, String, String)>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if ![&z, &x, &y].iter().all(|s| is_safe_path_segment(s)) {
         return Err(StatusCode::NOT_FOUND);
     }
+    serve_file(&state.tiles_dir.join(z).join(x).join(y), IMMUTABLE).await
+}
 
-    let body = tokio::fs::read(path)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-    let content_type = match path.extension().and_then(|e| e.to_str()) {
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
         Some("jpg" | "jpeg") => "image/jpeg",
         Some("png") => "image/png",
         Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        Some("tif" | "tiff") => "image/tiff",
+        Some("avif") => "image/avif",
+        Some("css") => "text/css",
+        Some("svg") => "image/svg+xml",
         _ => "application/octet-stream",
-    };
+    }
+}
+
+async fn serve_file(
+    path: &Path,
+    cache_control: &'static str,
+) -> Result<impl IntoResponse + use<>, StatusCode> {
+    if !path.is_file() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let body = tokio::fs::read(path)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
     Ok((
         [
-            (axum::http::header::CONTENT_TYPE, content_type),
-            (
-                axum::http::header::CACHE_CONTROL,
-                "public, max-age=31536000, immutable",
-            ),
+            (axum::http::header::CONTENT_TYPE, content_type(path)),
+            (axum::http::header::CACHE_CONTROL, cache_control),
         ],
         body,
     ))
 }
 
-fn scan_albums(photos_dir: &Path) -> Vec<Album> {
-    let mut albums = Vec::new();
+/// Returns the slugs and paths of the album directories.
+fn album_dirs(photos_dir: &Path) -> Vec<(String, PathBuf)> {
     let Ok(entries) = std::fs::read_dir(photos_dir) else {
-        return albums;
+        return Vec::new();
     };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry.path().is_dir() && !entry.file_name().to_string_lossy().starts_with('.')
+        })
+        .map(|entry| {
+            (
+                entry.file_name().to_string_lossy().to_string(),
+                entry.path(),
+            )
+        })
+        .collect()
+}
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_dir() || entry.file_name().to_string_lossy().starts_with('.') {
-            continue;
-        }
-        let slug = entry.file_name().to_string_lossy().to_string();
+fn scan_albums(photos_dir: &Path, order: &AlbumOrder, locale: &Locale) -> Vec<Album> {
+    let mut albums = Vec::new();
+    for (slug, path) in album_dirs(photos_dir) {
         let photos = list_photos(&path);
-        albums.push(load_album(&slug, &path, &photos));
+        albums.push(load_album(&slug, &path, &photos, locale));
     }
 
-    albums.sort_by(|a, b| match (&a.sort_date, &b.sort_date) {
-        (None, None) => a.title.cmp(&b.title),
-        (None, Some(_)) => std::cmp::Ordering::Greater,
-        (Some(_), None) => std::cmp::Ordering::Less,
-        (Some(ad), Some(bd)) => bd.cmp(ad),
+    albums.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| compare_albums(a, b, order))
     });
     albums
 }
 
-fn derive_sort_date(album_path: &Path, photos: &[Photo]) -> Option<String> {
+fn compare_albums(a: &Album, b: &Album, order: &AlbumOrder) -> Ordering {
+    match order {
+        AlbumOrder::Sort(AlbumSort::Newest) => compare_album_dates(a, b, true),
+        AlbumOrder::Sort(AlbumSort::Oldest) => compare_album_dates(a, b, false),
+        AlbumOrder::Sort(AlbumSort::Title) => a.title.cmp(&b.title),
+        AlbumOrder::Manual(slugs) => {
+            let position = |album: &Album| slugs.iter().position(|s| *s == album.slug);
+            match (position(a), position(b)) {
+                (Some(ap), Some(bp)) => ap.cmp(&bp),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => compare_album_dates(a, b, true),
+            }
+        }
+    }
+}
+
+/// Compares albums by date, putting undated albums last sorted by title.
+fn compare_album_dates(a: &Album, b: &Album, newest_first: bool) -> Ordering {
+    match (&a.sort_date, &b.sort_date) {
+        (None, None) => a.title.cmp(&b.title),
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(ad), Some(bd)) if newest_first => bd.cmp(ad),
+        (Some(ad), Some(bd)) => ad.cmp(bd),
+    }
+}
+
+fn derive_sort_date(album_path: &Path, photos: &[Photo], clock: &clock::Clock) -> Option<String> {
     photos
         .iter()
-        .filter_map(|p| exif::read_exif_date(&album_path.join(&p.filename)))
+        .filter_map(|p| clock.read(&album_path.join(&p.filename)))
+        .map(|taken| taken.to_exif_string())
         .max()
 }
 
-fn load_album(slug: &str, album_path: &Path, photos: &[Photo]) -> Album {
+fn load_album(slug: &str, album_path: &Path, photos: &[Photo], locale: &Locale) -> Album {
     let meta = load_meta(album_path);
-    let cover = photos.first().map(|p| p.filename.clone());
+    let mut cover = meta
+        .cover
+        .as_ref()
+        .and_then(|name| photos.iter().find(|p| p.filename == *name))
+        .or(photos.first())
+        .cloned();
+    // The cover thumbnail also depends on the focal point.
+    if let Some(cover) = &mut cover {
+        let key = format!("{}:{}", cover.version, meta.cover_focus.cache_dir_name());
+        cover.version = fingerprint(key.as_bytes());
+    }
+    let description = meta.description.unwrap_or_default();
+    let readme = std::fs::read_to_string(album_path.join("README.md")).unwrap_or_default();
     Album {
         title: meta.title.unwrap_or_else(|| slug_to_title(slug)),
-        description: meta.description.unwrap_or_default(),
+        description_html: markdown::render(&description),
+        description,
+        readme_html: markdown::render(&readme),
         timespan: meta
             .timespan
-            .unwrap_or_else(|| derive_timespan(album_path, photos)),
-        sort_date: derive_sort_date(album_path, photos),
+            .unwrap_or_else(|| derive_timespan(album_path, photos, locale, &meta.clock)),
+        location: None,
+        sort_date: derive_sort_date(album_path, photos, &meta.clock),
         slug: slug.to_string(),
         cover,
+        pinned: meta.pinned,
+        download: meta.download,
     }
 }
 
+/// Reads the `album.toml` of an album. Errors in it are logged and the
+/// defaults used instead, except for `location`: it's read on its own, and
+/// if that fails too the album is kept off the map, so that a typo in
+/// another setting can't reveal positions that were meant to be hidden.
 fn load_meta(album_path: &Path) -> AlbumMeta {
     let toml_path = album_path.join("album.toml");
-    std::fs::read_to_string(&toml_path)
-        .ok()
-        .and_then(|s| toml::from_str(&s).ok())
-        .unwrap_or_default()
+    let Ok(text) = std::fs::read_to_string(&toml_path) else {
+        return AlbumMeta::default();
+    };
+    let err = match toml::from_str(&text) {
+        Ok(meta) => return meta,
+        Err(err) => err,
+    };
+    tracing::warn!("invalid {}: {}", toml_path.display(), err);
+    let location = match text.parse::<toml::Table>() {
+        Ok(table) => match table.get("location") {
+            Some(value) => value.clone().try_into().ok(),
+            None => Some(geo::AlbumLocation::default()),
+        },
+        Err(_) => None,
+    };
+    AlbumMeta {
+        location: location.unwrap_or(geo::AlbumLocation::Gps(false)),
+        ..AlbumMeta::default()
+    }
 }
 
 fn list_photos(album_path: &Path) -> Vec<Photo> {
@@ -391,22 +1490,77 @@ fn list_photos(album_path: &Path) -> Vec<Photo> {
         return photos;
     };
 
+    let mut names = Vec::new();
     for entry in entries.flatten() {
         let name = entry.file_name().to_string_lossy().to_string();
-        let lower = name.to_lowercase();
-        if lower.ends_with(".jpg")
-            || lower.ends_with(".jpeg")
-            || lower.ends_with(".png")
-            || lower.ends_with(".webp")
-        {
-            photos.push(Photo { filename: name });
+        let path = Path::new(&name);
+        if formats::is_image(path) || raw::is_raw(path) {
+            names.push(name);
         }
     }
 
-    photos.sort_by(|a, b| a.filename.cmp(&b.filename));
+    // A RAW file is only shown if there's no developed image of it.
+    let developed: HashSet<String> = names
+        .iter()
+        .filter(|name| !raw::is_raw(Path::new(name)))
+        .map(|name| file_stem(name))
+        .collect();
+    for name in names {
+        if raw::is_raw(Path::new(&name)) && developed.contains(&file_stem(&name)) {
+            continue;
+        }
+        let version = file_version(&album_path.join(&name));
+        photos.push(Photo {
+            filename: name,
+            version,
+            placeholder: None,
+            dimensions: None,
+            medium: None,
+            animated: false,
+        });
+    }
+
+    sort_photos(album_path, &mut photos, &load_meta(album_path));
     photos
 }
 
+fn file_stem(name: &str) -> String {
+    Path::new(name)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase()
+}
+
+fn sort_photos(album_path: &Path, photos: &mut [Photo], meta: &AlbumMeta) {
+    match meta.sort {
+        PhotoSort::Filename => photos.sort_by(|a, b| a.filename.cmp(&b.filename)),
+        PhotoSort::Date => photos.sort_by_cached_key(|p| {
+            // By the moment in UTC, so photos from cameras set to different
+            // time zones interleave correctly.
+            let date = meta
+                .clock
+                .read(&album_path.join(&p.filename))
+                .map(|t| t.utc);
+            (date.is_none(), date, p.filename.clone())
+        }),
+    }
+
+    if meta.reverse {
+        photos.reverse();
+    }
+
+    if !meta.order.is_empty() {
+        // Stable sort, so unlisted photos keep their relative order.
+        photos.sort_by_key(|p| {
+            meta.order
+                .iter()
+                .position(|f| *f == p.filename)
+                .unwrap_or(usize::MAX)
+        });
+    }
+}
+
 fn slug_to_title(slug: &str) -> String {
     slug.replace('-', " ")
         .split_whitespace()
@@ -419,36 +1573,28 @@ fn slug_to_title(slug: &str) -> String {
         .join(" ")
 }
 
-fn derive_timespan(album_path: &Path, photos: &[Photo]) -> String {
+fn derive_timespan(
+    album_path: &Path,
+    photos: &[Photo],
+    locale: &Locale,
+    clock: &clock::Clock,
+) -> String {
     let mut dates: Vec<String> = Vec::new();
 
     for photo in photos {
-        if let Some(date) = exif::read_exif_date(&album_path.join(&photo.filename)) {
-            dates.push(date);
+        if let Some(taken) = clock.read(&album_path.join(&photo.filename)) {
+            dates.push(taken.to_exif_string());
         }
     }
 
-    format_date_range(&dates)
+    format_date_range(&dates, locale)
 }
 
-fn format_date_range(dates: &[String]) -> String {
-    if dates.is_empty() {
+fn format_date_range(dates: &[String], locale: &Locale) -> String {
+    let (Some(first), Some(last)) = (dates.iter().min(), dates.iter().max()) else {
         return String::new();
-    }
-
-    let mut sorted: Vec<&String> = dates.iter().collect();
-    sorted.sort();
-    let first = sorted[0];
-    let last = sorted[sorted.len() - 1];
-
-    let first_month = exif::format_year_month(first);
-    let last_month = exif::format_year_month(last);
-
-    if first_month == last_month {
-        first_month
-    } else {
-        format!("{} – {}", first_month, last_month)
-    }
+    };
+    timespan::format_range(first, last, locale)
 }
 
 #[cfg(test)]
@@ -484,7 +1630,15 @@ mod tests {
     fn derive_timespan_empty() {
         let dir = tempfile::tempdir().unwrap();
         let photos: Vec<Photo> = vec![];
-        assert_eq!(derive_timespan(dir.path(), &photos), "");
+        assert_eq!(
+            derive_timespan(
+                dir.path(),
+                &photos,
+                &Locale::default(),
+                &clock::Clock::default()
+            ),
+            ""
+        );
     }
 
     #[test]
@@ -493,8 +1647,21 @@ mod tests {
         fs::copy(fixture_path(), dir.path().join("photo.jpg")).unwrap();
         let photos = vec![Photo {
             filename: "photo.jpg".to_string(),
+            version: String::new(),
+            placeholder: None,
+            dimensions: None,
+            medium: None,
+            animated: false,
         }];
-        assert_eq!(derive_timespan(dir.path(), &photos), "February 2026");
+        assert_eq!(
+            derive_timespan(
+                dir.path(),
+                &photos,
+                &Locale::default(),
+                &clock::Clock::default()
+            ),
+            "1 February 2026"
+        );
     }
 
     #[test]
@@ -503,19 +1670,35 @@ mod tests {
         fs::write(dir.path().join("photo.jpg"), b"not a real jpeg").unwrap();
         let photos = vec![Photo {
             filename: "photo.jpg".to_string(),
+            version: String::new(),
+            placeholder: None,
+            dimensions: None,
+            medium: None,
+            animated: false,
         }];
-        assert_eq!(derive_timespan(dir.path(), &photos), "");
+        assert_eq!(
+            derive_timespan(
+                dir.path(),
+                &photos,
+                &Locale::default(),
+                &clock::Clock::default()
+            ),
+            ""
+        );
     }
 
     #[test]
     fn format_date_range_empty() {
-        assert_eq!(format_date_range(&[]), "");
+        assert_eq!(format_date_range(&[], &Locale::default()), "");
     }
 
     #[test]
     fn format_date_range_single() {
         let dates = vec!["2024:06:15 12:00:00".to_string()];
-        assert_eq!(format_date_range(&dates), "June 2024");
+        assert_eq!(
+            format_date_range(&dates, &Locale::default()),
+            "15 June 2024"
+        );
     }
 
     #[test]
@@ -524,7 +1707,23 @@ mod tests {
             "2024:06:15 12:00:00".to_string(),
             "2024:06:20 12:00:00".to_string(),
         ];
-        assert_eq!(format_date_range(&dates), "June 2024");
+        assert_eq!(
+            format_date_range(&dates, &Locale::default()),
+            "15–20 June 2024"
+        );
+    }
+
+    #[test]
+    fn format_date_range_localized() {
+        let dates = vec![
+            "2024:06:15 12:00:00".to_string(),
+            "2024:09:20 12:00:00".to_string(),
+        ];
+        let locale = Locale {
+            language: Language::Sv,
+            ..Default::default()
+        };
+        assert_eq!(format_date_range(&dates, &locale), "juni – september 2024");
     }
 
     #[test]
@@ -533,7 +1732,10 @@ mod tests {
             "2024:06:15 12:00:00".to_string(),
             "2024:09:20 12:00:00".to_string(),
         ];
-        assert_eq!(format_date_range(&dates), "June 2024 – September 2024");
+        assert_eq!(
+            format_date_range(&dates, &Locale::default()),
+            "June – September 2024"
+        );
     }
 
     #[test]
@@ -565,6 +1767,26 @@ mod tests {
         fs::write(dir.path().join("album.toml"), "not valid {{{{ toml").unwrap();
         let meta = load_meta(dir.path());
         assert!(meta.title.is_none());
+        // It's unknown whether the album should be on the map.
+        assert_eq!(meta.location, geo::AlbumLocation::Gps(false));
+    }
+
+    #[test]
+    fn load_meta_invalid_setting_keeps_location() {
+        let dir = tempfile::tempdir().unwrap();
+        let load = |toml: &str| {
+            fs::write(dir.path().join("album.toml"), toml).unwrap();
+            load_meta(dir.path())
+        };
+        let meta = load("title = \"Trip\"\nlocation = false\ntimezone = \"Mars/Olympus\"\n");
+        assert!(meta.title.is_none());
+        assert_eq!(meta.location, geo::AlbumLocation::Gps(false));
+        let meta = load("location = [60.17, 24.94]\ntimezone = \"Mars/Olympus\"\n");
+        assert_eq!(meta.location, geo::AlbumLocation::Fixed([60.17, 24.94]));
+        let meta = load("timezone = \"Mars/Olympus\"\n");
+        assert_eq!(meta.location, geo::AlbumLocation::Gps(true));
+        let meta = load("location = \"nowhere\"\n");
+        assert_eq!(meta.location, geo::AlbumLocation::Gps(false));
     }
 
     #[test]
@@ -580,6 +1802,16 @@ mod tests {
         assert_eq!(names, vec!["a.png", "b.jpg", "c.webp"]);
     }
 
+    #[test]
+    fn list_photos_raw_without_developed_image() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.jpg", "A.RAF", "b.RAF", "c.dng", "c.png", "d.txt"] {
+            fs::write(dir.path().join(name), b"").unwrap();
+        }
+        let photos = list_photos(dir.path());
+        assert_eq!(photo_names(&photos), vec!["a.jpg", "b.RAF", "c.png"]);
+    }
+
     #[test]
     fn list_photos_missing_dir() {
         let photos = list_photos(Path::new("/nonexistent/dir"));
@@ -601,7 +1833,7 @@ mod tests {
         fs::create_dir(dir.path().join(".hidden")).unwrap();
         fs::create_dir(dir.path().join("visible-album")).unwrap();
         fs::write(dir.path().join("a-file.txt"), b"").unwrap();
-        let albums = scan_albums(dir.path());
+        let albums = scan_albums(dir.path(), &AlbumOrder::default(), &Locale::default());
         assert_eq!(albums.len(), 1);
         assert_eq!(albums[0].slug, "visible-album");
     }
@@ -611,14 +1843,18 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         fs::create_dir(dir.path().join("z-album")).unwrap();
         fs::create_dir(dir.path().join("a-album")).unwrap();
-        let albums = scan_albums(dir.path());
+        let albums = scan_albums(dir.path(), &AlbumOrder::default(), &Locale::default());
         assert_eq!(albums[0].title, "A Album");
         assert_eq!(albums[1].title, "Z Album");
     }
 
     #[test]
     fn scan_albums_nonexistent_dir() {
-        let albums = scan_albums(Path::new("/nonexistent"));
+        let albums = scan_albums(
+            Path::new("/nonexistent"),
+            &AlbumOrder::default(),
+            &Locale::default(),
+        );
         assert!(albums.is_empty());
     }
 
@@ -634,11 +1870,14 @@ mod tests {
         .unwrap();
         fs::write(album_dir.join("a.jpg"), b"").unwrap();
         let photos = list_photos(&album_dir);
-        let album = load_album("test", &album_dir, &photos);
+        let album = load_album("test", &album_dir, &photos, &Locale::default());
         assert_eq!(album.title, "Custom Title");
         assert_eq!(album.description, "Desc");
         assert_eq!(album.timespan, "2024");
-        assert_eq!(album.cover.as_deref(), Some("a.jpg"));
+        assert_eq!(
+            album.cover.as_ref().map(|p| p.filename.as_str()),
+            Some("a.jpg")
+        );
         assert_eq!(album.slug, "test");
     }
 
@@ -648,7 +1887,7 @@ mod tests {
         let album_dir = dir.path().join("my-album");
         fs::create_dir(&album_dir).unwrap();
         let photos = list_photos(&album_dir);
-        let album = load_album("my-album", &album_dir, &photos);
+        let album = load_album("my-album", &album_dir, &photos, &Locale::default());
         assert_eq!(album.title, "My Album");
         assert_eq!(album.description, "");
         assert_eq!(album.timespan, "");
@@ -693,7 +1932,10 @@ mod tests {
     #[test]
     fn derive_sort_date_no_photos() {
         let dir = tempfile::tempdir().unwrap();
-        assert_eq!(derive_sort_date(dir.path(), &[]), None);
+        assert_eq!(
+            derive_sort_date(dir.path(), &[], &clock::Clock::default()),
+            None
+        );
     }
 
     #[test]
@@ -702,8 +1944,16 @@ mod tests {
         fs::write(dir.path().join("photo.jpg"), b"not a real jpeg").unwrap();
         let photos = vec![Photo {
             filename: "photo.jpg".to_string(),
+            version: String::new(),
+            placeholder: None,
+            dimensions: None,
+            medium: None,
+            animated: false,
         }];
-        assert_eq!(derive_sort_date(dir.path(), &photos), None);
+        assert_eq!(
+            derive_sort_date(dir.path(), &photos, &clock::Clock::default()),
+            None
+        );
     }
 
     #[test]
@@ -712,9 +1962,14 @@ mod tests {
         fs::copy(fixture_path(), dir.path().join("photo.jpg")).unwrap();
         let photos = vec![Photo {
             filename: "photo.jpg".to_string(),
+            version: String::new(),
+            placeholder: None,
+            dimensions: None,
+            medium: None,
+            animated: false,
         }];
         assert_eq!(
-            derive_sort_date(dir.path(), &photos).as_deref(),
+            derive_sort_date(dir.path(), &photos, &clock::Clock::default()).as_deref(),
             Some("2026-02-01 15:01:06")
         );
     }
@@ -727,7 +1982,7 @@ mod tests {
         fs::create_dir(&dated).unwrap();
         fs::create_dir(&undated).unwrap();
         fs::copy(fixture_path(), dated.join("photo.jpg")).unwrap();
-        let albums = scan_albums(dir.path());
+        let albums = scan_albums(dir.path(), &AlbumOrder::default(), &Locale::default());
         assert_eq!(albums[0].slug, "z-album");
         assert_eq!(albums[1].slug, "a-album");
     }
@@ -743,8 +1998,303 @@ mod tests {
             PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/DSCF0263.jpg"); // 2026-03-20
         fs::copy(&newer_fixture, newer.join("photo.jpg")).unwrap();
         fs::copy(fixture_path(), older.join("photo.jpg")).unwrap(); // 2026-02-01
-        let albums = scan_albums(dir.path());
+        let albums = scan_albums(dir.path(), &AlbumOrder::default(), &Locale::default());
         assert_eq!(albums[0].slug, "newer-album");
         assert_eq!(albums[1].slug, "older-album");
     }
+
+    fn dated_albums() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let older = dir.path().join("older-album");
+        let newer = dir.path().join("newer-album");
+        let undated = dir.path().join("undated-album");
+        fs::create_dir(&older).unwrap();
+        fs::create_dir(&newer).unwrap();
+        fs::create_dir(&undated).unwrap();
+        let newer_fixture =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/DSCF0263.jpg");
+        fs::copy(&newer_fixture, newer.join("photo.jpg")).unwrap();
+        fs::copy(fixture_path(), older.join("photo.jpg")).unwrap();
+        dir
+    }
+
+    fn slugs(albums: &[Album]) -> Vec<&str> {
+        albums.iter().map(|a| a.slug.as_str()).collect()
+    }
+
+    #[test]
+    fn scan_albums_sorts_oldest_first() {
+        let dir = dated_albums();
+        let albums = scan_albums(
+            dir.path(),
+            &AlbumOrder::Sort(AlbumSort::Oldest),
+            &Locale::default(),
+        );
+        assert_eq!(
+            slugs(&albums),
+            vec!["older-album", "newer-album", "undated-album"]
+        );
+    }
+
+    #[test]
+    fn scan_albums_sorts_by_title_only() {
+        let dir = dated_albums();
+        let albums = scan_albums(
+            dir.path(),
+            &AlbumOrder::Sort(AlbumSort::Title),
+            &Locale::default(),
+        );
+        assert_eq!(
+            slugs(&albums),
+            vec!["newer-album", "older-album", "undated-album"]
+        );
+    }
+
+    #[test]
+    fn scan_albums_manual_order() {
+        let dir = dated_albums();
+        let order =
+            AlbumOrder::Manual(vec!["undated-album".to_string(), "older-album".to_string()]);
+        let albums = scan_albums(dir.path(), &order, &Locale::default());
+        assert_eq!(
+            slugs(&albums),
+            vec!["undated-album", "older-album", "newer-album"]
+        );
+    }
+
+    #[test]
+    fn scan_albums_pinned_first() {
+        let dir = dated_albums();
+        fs::write(
+            dir.path().join("undated-album").join("album.toml"),
+            "pinned = true\n",
+        )
+        .unwrap();
+        let albums = scan_albums(dir.path(), &AlbumOrder::default(), &Locale::default());
+        assert_eq!(
+            slugs(&albums),
+            vec!["undated-album", "newer-album", "older-album"]
+        );
+    }
+
+    #[test]
+    fn site_config_album_order() {
+        let parse = |s: &str| toml::from_str::<SiteConfig>(s).unwrap().album_order;
+        assert_eq!(parse(""), AlbumOrder::Sort(AlbumSort::Newest));
+        assert_eq!(
+            parse("album_order = \"oldest\""),
+            AlbumOrder::Sort(AlbumSort::Oldest)
+        );
+        assert_eq!(
+            parse("album_order = \"title\""),
+            AlbumOrder::Sort(AlbumSort::Title)
+        );
+        assert_eq!(
+            parse("album_order = [\"b\", \"a\"]"),
+            AlbumOrder::Manual(vec!["b".to_string(), "a".to_string()])
+        );
+    }
+
+    fn photo_names(photos: &[Photo]) -> Vec<&str> {
+        photos.iter().map(|p| p.filename.as_str()).collect()
+    }
+
+    #[test]
+    fn list_photos_reverse() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.jpg"), b"").unwrap();
+        fs::write(dir.path().join("b.jpg"), b"").unwrap();
+        fs::write(dir.path().join("album.toml"), "reverse = true\n").unwrap();
+        let photos = list_photos(dir.path());
+        assert_eq!(photo_names(&photos), vec!["b.jpg", "a.jpg"]);
+    }
+
+    #[test]
+    fn list_photos_sort_by_date() {
+        let dir = tempfile::tempdir().unwrap();
+        let newer_fixture =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/DSCF0263.jpg");
+        fs::copy(&newer_fixture, dir.path().join("a.jpg")).unwrap(); // 2026-03-20
+        fs::copy(fixture_path(), dir.path().join("b.jpg")).unwrap(); // 2026-02-01
+        fs::write(dir.path().join("0-undated.jpg"), b"").unwrap();
+        fs::write(dir.path().join("album.toml"), "sort = \"date\"\n").unwrap();
+        let photos = list_photos(dir.path());
+        assert_eq!(
+            photo_names(&photos),
+            vec!["b.jpg", "a.jpg", "0-undated.jpg"]
+        );
+    }
+
+    #[test]
+    fn list_photos_explicit_order() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.jpg", "b.jpg", "c.jpg", "d.jpg"] {
+            fs::write(dir.path().join(name), b"").unwrap();
+        }
+        fs::write(
+            dir.path().join("album.toml"),
+            "order = [\"c.jpg\", \"a.jpg\", \"missing.jpg\"]\n",
+        )
+        .unwrap();
+        let photos = list_photos(dir.path());
+        assert_eq!(
+            photo_names(&photos),
+            vec!["c.jpg", "a.jpg", "b.jpg", "d.jpg"]
+        );
+    }
+
+    #[test]
+    fn load_album_explicit_cover() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.jpg"), b"").unwrap();
+        fs::write(dir.path().join("b.jpg"), b"").unwrap();
+        fs::write(dir.path().join("album.toml"), "cover = \"b.jpg\"\n").unwrap();
+        let photos = list_photos(dir.path());
+        let album = load_album("test", dir.path(), &photos, &Locale::default());
+        assert_eq!(
+            album.cover.as_ref().map(|p| p.filename.as_str()),
+            Some("b.jpg")
+        );
+    }
+
+    #[test]
+    fn load_album_missing_cover_falls_back_to_first() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.jpg"), b"").unwrap();
+        fs::write(dir.path().join("album.toml"), "cover = \"nope.jpg\"\n").unwrap();
+        let photos = list_photos(dir.path());
+        let album = load_album("test", dir.path(), &photos, &Locale::default());
+        assert_eq!(
+            album.cover.as_ref().map(|p| p.filename.as_str()),
+            Some("a.jpg")
+        );
+    }
+
+    #[test]
+    fn load_meta_cover_focus() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("album.toml"),
+            "cover_focus = [0.25, 0.75]\n",
+        )
+        .unwrap();
+        let meta = load_meta(dir.path());
+        assert_eq!(meta.cover_focus, FocalPoint(0.25, 0.75));
+        assert_eq!(meta.cover_focus.cache_dir_name(), "cover-25-75");
+    }
+
+    #[test]
+    fn crop_around_center() {
+        // Landscape wider than 3:2 is cropped horizontally.
+        assert_eq!(
+            crop_around(1600, 800, FocalPoint::default()),
+            (200, 0, 1200, 800)
+        );
+        // Portrait is cropped vertically.
+        assert_eq!(
+            crop_around(600, 1200, FocalPoint::default()),
+            (0, 400, 600, 400)
+        );
+    }
+
+    #[test]
+    fn crop_around_tiny_image() {
+        assert_eq!(crop_around(1, 1, FocalPoint::default()), (0, 0, 1, 1));
+    }
+
+    #[test]
+    fn crop_around_clamps_to_edges() {
+        assert_eq!(
+            crop_around(1600, 800, FocalPoint(0.0, 0.5)),
+            (0, 0, 1200, 800)
+        );
+        assert_eq!(
+            crop_around(1600, 800, FocalPoint(0.9, 0.5)),
+            (400, 0, 1200, 800)
+        );
+        assert_eq!(
+            crop_around(600, 1200, FocalPoint(0.5, 0.3)),
+            (0, 160, 600, 400)
+        );
+    }
+
+    #[test]
+    fn load_album_markdown_description_and_readme() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("album.toml"),
+            "description = \"A *short* trip.\"\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), "# Day one\n\nWe walked.\n").unwrap();
+        let album = load_album("test", dir.path(), &[], &Locale::default());
+        assert_eq!(album.description, "A *short* trip.");
+        assert_eq!(album.description_html, "<p>A <em>short</em> trip.</p>\n");
+        assert_eq!(album.readme_html, "<h1>Day one</h1>\n<p>We walked.</p>\n");
+    }
+
+    #[test]
+    fn load_album_without_readme() {
+        let dir = tempfile::tempdir().unwrap();
+        let album = load_album("test", dir.path(), &[], &Locale::default());
+        assert_eq!(album.description_html, "");
+        assert_eq!(album.readme_html, "");
+    }
+
+    #[test]
+    fn dimensions_fit() {
+        let landscape = Dimensions {
+            width: 6000,
+            height: 4000,
+        };
+        assert_eq!(
+            landscape.fit(1200),
+            Dimensions {
+                width: 1200,
+                height: 800
+            }
+        );
+        let portrait = Dimensions {
+            width: 3,
+            height: 1000,
+        };
+        assert_eq!(
+            portrait.fit(400),
+            Dimensions {
+                width: 1,
+                height: 400
+            }
+        );
+    }
+
+    #[test]
+    fn dimensions_fit_matches_thumbnail() {
+        let img = image::open(fixture_path()).unwrap();
+        let thumb = img.resize(SMALL_SIZE, SMALL_SIZE, FilterType::Lanczos3);
+        let dimensions = Dimensions {
+            width: img.width(),
+            height: img.height(),
+        };
+        assert_eq!(
+            dimensions.fit(SMALL_SIZE),
+            Dimensions {
+                width: thumb.width(),
+                height: thumb.height()
+            }
+        );
+    }
+
+    #[test]
+    fn load_dimensions_from_header() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::copy(fixture_path(), dir.path().join("a.jpg")).unwrap();
+        fs::write(dir.path().join("b.jpg"), b"not a real jpeg").unwrap();
+        let mut photos = list_photos(dir.path());
+        load_dimensions(dir.path(), &mut photos);
+        let (width, height) = image::image_dimensions(fixture_path()).unwrap();
+        assert_eq!(photos[0].dimensions, Some(Dimensions { width, height }));
+        assert!(photos[0].medium.is_some());
+        assert_eq!(photos[1].dimensions, None);
+        assert_eq!(photos[1].medium, None);
+    }
 }
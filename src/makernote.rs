@@ -0,0 +1,296 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! Decoding of vendor-specific MakerNote data, which kamadak-exif exposes
+//! only as raw bytes. Only Fujifilm is supported so far; other vendors can
+//! be added to [`parse`].
+
+/// Camera settings decoded from the MakerNote.
+#[derive(Default, Debug, PartialEq)]
+pub struct MakerNote {
+    pub film_simulation: Option<&'static str>,
+    pub grain_effect: Option<Grain>,
+    pub color_chrome: Option<Strength>,
+    pub color_chrome_blue: Option<Strength>,
+    pub dynamic_range: Option<DynamicRange>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strength {
+    Off,
+    Weak,
+    Strong,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GrainSize {
+    Small,
+    Large,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grain {
+    pub strength: Strength,
+    /// Not recorded by older cameras.
+    pub size: Option<GrainSize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DynamicRange {
+    pub auto: bool,
+    /// The dynamic range used, e.g. 400 for DR400.
+    pub percent: Option<u32>,
+}
+
+/// Decodes the MakerNote of a camera from the given maker, as recorded in
+/// the EXIF `Make` field.
+pub fn parse(make: &str, data: &[u8]) -> Option<MakerNote> {
+    if make.trim().eq_ignore_ascii_case("FUJIFILM") {
+        parse_fujifilm(data)
+    } else {
+        None
+    }
+}
+
+const FUJIFILM_MAGIC: &[u8] = b"FUJIFILM";
+
+const TAG_SATURATION: u16 = 0x1003;
+const TAG_GRAIN_EFFECT: u16 = 0x1047;
+const TAG_COLOR_CHROME: u16 = 0x1048;
+const TAG_GRAIN_SIZE: u16 = 0x104c;
+const TAG_COLOR_CHROME_BLUE: u16 = 0x104e;
+const TAG_FILM_MODE: u16 = 0x1401;
+const TAG_DYNAMIC_RANGE_SETTING: u16 = 0x1402;
+const TAG_DEVELOPMENT_DYNAMIC_RANGE: u16 = 0x1403;
+
+/// Fujifilm MakerNotes start with "FUJIFILM" and the offset of a
+/// little-endian IFD, with offsets relative to the start of the MakerNote.
+fn parse_fujifilm(data: &[u8]) -> Option<MakerNote> {
+    if !data.starts_with(FUJIFILM_MAGIC) {
+        return None;
+    }
+    let ifd = u32_at(data, 8)? as usize;
+    let count = usize::from(u16_at(data, ifd)?);
+
+    let mut note = MakerNote::default();
+    let mut saturation = None;
+    let mut grain_size = None;
+    let mut dr_setting = None;
+    for i in 0..count {
+        let entry = ifd + 2 + 12 * i;
+        let tag = u16_at(data, entry)?;
+        let kind = u16_at(data, entry + 2)?;
+        // All of the values used here fit into the entry.
+        let value = match kind {
+            3 | 8 => u32::from(u16_at(data, entry + 8)?),
+            4 | 9 => u32_at(data, entry + 8)?,
+            _ => continue,
+        };
+        match tag {
+            TAG_FILM_MODE => note.film_simulation = film_mode(value),
+            TAG_SATURATION => saturation = Some(value),
+            TAG_GRAIN_EFFECT => {
+                note.grain_effect = strength(value).map(|strength| Grain {
+                    strength,
+                    size: None,
+                })
+            }
+            TAG_GRAIN_SIZE => {
+                grain_size = match value {
+                    16 => Some(GrainSize::Small),
+                    32 => Some(GrainSize::Large),
+                    _ => None,
+                }
+            }
+            TAG_COLOR_CHROME => note.color_chrome = strength(value),
+            TAG_COLOR_CHROME_BLUE => note.color_chrome_blue = strength(value),
+            TAG_DYNAMIC_RANGE_SETTING => dr_setting = Some(value),
+            TAG_DEVELOPMENT_DYNAMIC_RANGE => {
+                let dr = note.dynamic_range.get_or_insert(DynamicRange {
+                    auto: false,
+                    percent: None,
+                });
+                dr.percent = Some(value).filter(|&v| v > 0);
+            }
+            _ => {}
+        }
+    }
+
+    // Monochrome simulations are recorded as a saturation setting instead.
+    if note.film_simulation.is_none() {
+        note.film_simulation = saturation.and_then(monochrome_mode);
+    }
+    if let Some(grain) = &mut note.grain_effect
+        && grain.strength != Strength::Off
+    {
+        grain.size = grain_size;
+    }
+    if let Some(setting) = dr_setting {
+        let dr = note.dynamic_range.get_or_insert(DynamicRange {
+            auto: false,
+            percent: None,
+        });
+        dr.auto = setting == 0;
+        if dr.percent.is_none() {
+            dr.percent = match setting {
+                0x100 => Some(100),
+                0x200 => Some(200),
+                0x201 => Some(400),
+                _ => None,
+            };
+        }
+    }
+    Some(note)
+}
+
+fn film_mode(value: u32) -> Option<&'static str> {
+    Some(match value {
+        0x000 => "Provia",
+        0x100 => "Studio Portrait",
+        0x110 => "Studio Portrait Enhanced Saturation",
+        0x120 => "Astia",
+        0x130 => "Studio Portrait Increased Sharpness",
+        0x200 | 0x400 => "Velvia",
+        0x300 => "Studio Portrait Ex",
+        0x500 => "Pro Neg. Std",
+        0x501 => "Pro Neg. Hi",
+        0x600 => "Classic Chrome",
+        0x700 => "Eterna",
+        0x800 => "Classic Negative",
+        0x900 => "Eterna Bleach Bypass",
+        0xa00 => "Nostalgic Neg.",
+        0xb00 => "Reala Ace",
+        _ => return None,
+    })
+}
+
+fn monochrome_mode(saturation: u32) -> Option<&'static str> {
+    Some(match saturation {
+        0x300 => "Monochrome",
+        0x301 => "Monochrome + R",
+        0x302 => "Monochrome + Ye",
+        0x303 => "Monochrome + G",
+        0x310 => "Sepia",
+        0x500 => "Acros",
+        0x501 => "Acros + R",
+        0x502 => "Acros + Ye",
+        0x503 => "Acros + G",
+        _ => return None,
+    })
+}
+
+fn strength(value: u32) -> Option<Strength> {
+    match value {
+        0 => Some(Strength::Off),
+        32 => Some(Strength::Weak),
+        64 => Some(Strength::Strong),
+        _ => None,
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Builds a Fujifilm MakerNote with the given SHORT entries.
+    pub fn fujifilm_note(entries: &[(u16, u16)]) -> Vec<u8> {
+        let mut note = FUJIFILM_MAGIC.to_vec();
+        note.extend_from_slice(&12u32.to_le_bytes());
+        note.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for &(tag, value) in entries {
+            note.extend_from_slice(&tag.to_le_bytes());
+            note.extend_from_slice(&3u16.to_le_bytes());
+            note.extend_from_slice(&1u32.to_le_bytes());
+            note.extend_from_slice(&u32::from(value).to_le_bytes());
+        }
+        note.extend_from_slice(&0u32.to_le_bytes());
+        note
+    }
+
+    #[test]
+    fn parse_fujifilm_recipe() {
+        let data = fujifilm_note(&[
+            (TAG_SATURATION, 0),
+            (TAG_GRAIN_EFFECT, 32),
+            (TAG_COLOR_CHROME, 64),
+            (TAG_GRAIN_SIZE, 32),
+            (TAG_COLOR_CHROME_BLUE, 0),
+            (TAG_FILM_MODE, 0x800),
+            (TAG_DYNAMIC_RANGE_SETTING, 1),
+            (TAG_DEVELOPMENT_DYNAMIC_RANGE, 400),
+        ]);
+        let note = parse("FUJIFILM", &data).unwrap();
+        assert_eq!(
+            note,
+            MakerNote {
+                film_simulation: Some("Classic Negative"),
+                grain_effect: Some(Grain {
+                    strength: Strength::Weak,
+                    size: Some(GrainSize::Large),
+                }),
+                color_chrome: Some(Strength::Strong),
+                color_chrome_blue: Some(Strength::Off),
+                dynamic_range: Some(DynamicRange {
+                    auto: false,
+                    percent: Some(400),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_fujifilm_monochrome() {
+        let data = fujifilm_note(&[(TAG_SATURATION, 0x501), (TAG_GRAIN_EFFECT, 0)]);
+        let note = parse("FUJIFILM", &data).unwrap();
+        assert_eq!(note.film_simulation, Some("Acros + R"));
+        assert_eq!(
+            note.grain_effect,
+            Some(Grain {
+                strength: Strength::Off,
+                size: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_fujifilm_auto_dynamic_range() {
+        let data = fujifilm_note(&[
+            (TAG_DYNAMIC_RANGE_SETTING, 0),
+            (TAG_DEVELOPMENT_DYNAMIC_RANGE, 200),
+        ]);
+        let note = parse("FUJIFILM", &data).unwrap();
+        assert_eq!(
+            note.dynamic_range,
+            Some(DynamicRange {
+                auto: true,
+                percent: Some(200),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_other_vendor_or_garbage() {
+        let data = fujifilm_note(&[(TAG_FILM_MODE, 0x600)]);
+        assert!(parse("Canon", &data).is_none());
+        assert!(parse("FUJIFILM", b"FUJIFILM").is_none());
+        assert!(parse("FUJIFILM", b"Nikon\0").is_none());
+    }
+
+    #[test]
+    fn parse_truncated_note() {
+        let mut data = fujifilm_note(&[(TAG_FILM_MODE, 0x600), (TAG_GRAIN_EFFECT, 64)]);
+        data.truncate(30);
+        assert!(parse("FUJIFILM", &data).is_none());
+    }
+}
@@ -0,0 +1,45 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+use pulldown_cmark::{Options, Parser};
+
+/// Renders Markdown to HTML with anything unsafe (scripts, event handlers,
+/// `javascript:` links) removed.
+pub fn render(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, Parser::new_ext(markdown, options));
+    ammonia::clean(&html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_paragraphs_and_links() {
+        assert_eq!(
+            render("First.\n\nSee [map](https://example.com)."),
+            "<p>First.</p>\n<p>See <a href=\"https://example.com\" rel=\"noopener noreferrer\">map</a>.</p>\n"
+        );
+    }
+
+    #[test]
+    fn render_strips_scripts() {
+        let html = render("Hi <script>alert(1)</script> <img src=x onerror=alert(1)>");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onerror"));
+    }
+
+    #[test]
+    fn render_strips_javascript_links() {
+        let html = render("[click](javascript:alert(1))");
+        assert!(!html.contains("javascript:"));
+    }
+
+    #[test]
+    fn render_empty() {
+        assert_eq!(render(""), "");
+    }
+}
@@ -0,0 +1,155 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! Low-quality image placeholders: tiny previews inlined into the page as
+//! data URIs so that the grid has something to paint before the thumbnails
+//! load. They're created as a by-product of thumbnail generation and cached
+//! next to the thumbnails.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use image::DynamicImage;
+use image::imageops::FilterType;
+
+use crate::is_stale;
+
+const PLACEHOLDER_SIZE: u32 = 16;
+
+/// The image that a placeholder stands in for.
+#[derive(Clone, Copy)]
+pub enum Variant<'a> {
+    /// The whole photo, as in the small and medium thumbnails.
+    Photo,
+    /// A cover thumbnail, by the name of its cache directory such as
+    /// "cover-50-30", since the crop depends on the focal point.
+    Cover(&'a str),
+}
+
+fn cache_path(cache_dir: &Path, album: &str, variant: Variant, filename: &str) -> PathBuf {
+    let dir = cache_dir.join(album).join("placeholder");
+    let dir = match variant {
+        Variant::Photo => dir,
+        Variant::Cover(name) => dir.join(name),
+    };
+    dir.join(format!("{}.txt", filename))
+}
+
+/// Encodes a tiny version of `img` as a PNG data URI.
+pub fn encode(img: &DynamicImage) -> Option<String> {
+    let tiny = img
+        .resize(PLACEHOLDER_SIZE, PLACEHOLDER_SIZE, FilterType::Triangle)
+        .into_rgb8();
+    let mut png = Vec::new();
+    tiny.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    Some(format!("data:image/png;base64,{}", STANDARD.encode(png)))
+}
+
+/// Caches the placeholder for `original`, unless an up-to-date one exists.
+pub fn store(
+    cache_dir: &Path,
+    album: &str,
+    variant: Variant,
+    filename: &str,
+    original: &Path,
+    img: &DynamicImage,
+) {
+    let path = cache_path(cache_dir, album, variant, filename);
+    if path.is_file() && !is_stale(original, &path) {
+        return;
+    }
+    let Some(data_uri) = encode(img) else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+    std::fs::write(path, data_uri).ok();
+}
+
+/// Returns the cached placeholder for `original`, if there's an up-to-date
+/// one.
+pub fn load(
+    cache_dir: &Path,
+    album: &str,
+    variant: Variant,
+    filename: &str,
+    original: &Path,
+) -> Option<String> {
+    let path = cache_path(cache_dir, album, variant, filename);
+    if is_stale(original, &path) {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            width,
+            height,
+            image::Rgb([200, 100, 50]),
+        ))
+    }
+
+    #[test]
+    fn encode_data_uri() {
+        let uri = encode(&image(300, 200)).unwrap();
+        let data = uri.strip_prefix("data:image/png;base64,").unwrap();
+        let png = STANDARD.decode(data).unwrap();
+        let tiny = image::load_from_memory(&png).unwrap();
+        assert_eq!((tiny.width(), tiny.height()), (16, 11));
+        assert_eq!(tiny.into_rgb8().get_pixel(0, 0).0, [200, 100, 50]);
+    }
+
+    #[test]
+    fn store_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("photo.jpg");
+        std::fs::write(&original, b"").unwrap();
+        let cache_dir = dir.path().join("cache");
+
+        let photo = Variant::Photo;
+        assert!(load(&cache_dir, "album", photo, "photo.jpg", &original).is_none());
+        store(
+            &cache_dir,
+            "album",
+            photo,
+            "photo.jpg",
+            &original,
+            &image(30, 20),
+        );
+        let uri = load(&cache_dir, "album", photo, "photo.jpg", &original).unwrap();
+        assert!(uri.starts_with("data:image/png;base64,"));
+
+        // Each crop of the cover has its own placeholder.
+        let cover = Variant::Cover("cover-50-30");
+        assert!(load(&cache_dir, "album", cover, "photo.jpg", &original).is_none());
+        store(
+            &cache_dir,
+            "album",
+            cover,
+            "photo.jpg",
+            &original,
+            &image(30, 20),
+        );
+        assert!(load(&cache_dir, "album", cover, "photo.jpg", &original).is_some());
+        assert!(
+            load(
+                &cache_dir,
+                "album",
+                Variant::Cover("cover-50-50"),
+                "photo.jpg",
+                &original
+            )
+            .is_none()
+        );
+    }
+}
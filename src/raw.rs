@@ -0,0 +1,504 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! RAW files are shown through the full-size JPEG preview that cameras embed
+//! in them. The preview is located by parsing just enough of the container:
+//! the Fujifilm RAF header, or the TIFF structure that DNG, CR2, NEF and ARW
+//! files are built on. Extracted previews are cached next to the thumbnails.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::is_stale;
+
+pub const EXTENSIONS: &[&str] = &["raf", "dng", "cr2", "nef", "arw"];
+
+const RAF_MAGIC: &[u8] = b"FUJIFILMCCD-RAW ";
+/// Offset of the big-endian JPEG offset and length in the RAF header.
+const RAF_JPEG_POINTER: u64 = 84;
+
+const TAG_COMPRESSION: u16 = 0x0103;
+const TAG_STRIP_OFFSETS: u16 = 0x0111;
+const TAG_STRIP_BYTE_COUNTS: u16 = 0x0117;
+const TAG_SUB_IFDS: u16 = 0x014a;
+const TAG_JPEG_OFFSET: u16 = 0x0201;
+const TAG_JPEG_LENGTH: u16 = 0x0202;
+/// Old-style and new-style JPEG compression.
+const JPEG_COMPRESSION: [u32; 2] = [6, 7];
+/// Guards against cyclic or absurdly large IFD structures.
+const MAX_IFDS: usize = 32;
+
+/// Byte range of the embedded JPEG within the RAW file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Preview {
+    offset: u64,
+    len: u64,
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+pub fn is_raw(path: &Path) -> bool {
+    has_extension(path, EXTENSIONS)
+}
+
+/// Name of the JPEG that stands in for a RAW file, e.g. in cache
+/// directories: `DSCF0001.RAF.jpg`.
+fn preview_name(filename: &str) -> String {
+    format!("{}.jpg", filename)
+}
+
+fn find_preview<R: Read + Seek>(r: &mut R) -> Option<Preview> {
+    let mut magic = [0; 16];
+    r.seek(SeekFrom::Start(0)).ok()?;
+    r.read_exact(&mut magic).ok()?;
+    if magic == RAF_MAGIC {
+        r.seek(SeekFrom::Start(RAF_JPEG_POINTER)).ok()?;
+        let offset = read_u32(r, true).ok()?;
+        let len = read_u32(r, true).ok()?;
+        let preview = Preview {
+            offset: offset.into(),
+            len: len.into(),
+        };
+        jpeg_dimensions(r, preview)?;
+        return Some(preview);
+    }
+    find_tiff_preview(r)
+}
+
+/// Picks the largest decodable JPEG referenced from any IFD. The raw sensor
+/// data in DNG files is also "JPEG" (lossless), but it has a different frame
+/// type and is skipped.
+fn find_tiff_preview<R: Read + Seek>(r: &mut R) -> Option<Preview> {
+    r.seek(SeekFrom::Start(0)).ok()?;
+    let mut header = [0; 8];
+    r.read_exact(&mut header).ok()?;
+    let big_endian = match &header[..4] {
+        b"II*\0" => false,
+        b"MM\0*" => true,
+        _ => return None,
+    };
+    let first = read_u32(&mut &header[4..], big_endian).ok()?;
+
+    let mut pending = vec![u64::from(first)];
+    let mut visited = Vec::new();
+    let mut best: Option<(u64, Preview)> = None;
+    while let Some(ifd) = pending.pop() {
+        if ifd == 0 || visited.contains(&ifd) || visited.len() >= MAX_IFDS {
+            continue;
+        }
+        visited.push(ifd);
+        let Ok((entries, next)) = read_ifd(r, ifd, big_endian) else {
+            continue;
+        };
+        let value = |tag: u16| entries.iter().find(|e| e.tag == tag).map(|e| &e.values);
+        let single = |tag: u16| value(tag).filter(|v| v.len() == 1).map(|v| v[0]);
+
+        let candidate = match (single(TAG_JPEG_OFFSET), single(TAG_JPEG_LENGTH)) {
+            (Some(offset), Some(len)) => Some((offset, len)),
+            _ => match (single(TAG_STRIP_OFFSETS), single(TAG_STRIP_BYTE_COUNTS)) {
+                (Some(offset), Some(len))
+                    if single(TAG_COMPRESSION).is_some_and(|c| JPEG_COMPRESSION.contains(&c)) =>
+                {
+                    Some((offset, len))
+                }
+                _ => None,
+            },
+        };
+        if let Some((offset, len)) = candidate {
+            let preview = Preview {
+                offset: offset.into(),
+                len: len.into(),
+            };
+            if let Some((width, height)) = jpeg_dimensions(r, preview) {
+                let area = u64::from(width) * u64::from(height);
+                if best.is_none_or(|(best_area, _)| area > best_area) {
+                    best = Some((area, preview));
+                }
+            }
+        }
+
+        if let Some(sub_ifds) = value(TAG_SUB_IFDS) {
+            pending.extend(sub_ifds.iter().map(|&o| u64::from(o)));
+        }
+        pending.push(next.into());
+    }
+    best.map(|(_, preview)| preview)
+}
+
+struct IfdEntry {
+    tag: u16,
+    values: Vec<u32>,
+}
+
+/// Reads the SHORT and LONG valued entries of an IFD, which are all that's
+/// needed for finding the preview, and the offset of the next IFD.
+fn read_ifd<R: Read + Seek>(
+    r: &mut R,
+    offset: u64,
+    big_endian: bool,
+) -> io::Result<(Vec<IfdEntry>, u32)> {
+    r.seek(SeekFrom::Start(offset))?;
+    let count = read_u16(r, big_endian)?;
+    let mut raw = vec![0; 12 * usize::from(count)];
+    r.read_exact(&mut raw)?;
+    let next = read_u32(r, big_endian)?;
+
+    let mut entries = Vec::new();
+    for chunk in raw.chunks_exact(12) {
+        // The chunk is always long enough, so these can't fail.
+        let u16_at = |i: usize| read_u16(&mut &chunk[i..], big_endian).unwrap_or(0);
+        let u32_at = |i: usize| read_u32(&mut &chunk[i..], big_endian).unwrap_or(0);
+        let tag = u16_at(0);
+        let count = u32_at(4);
+        let size = match u16_at(2) {
+            3 => 2,
+            4 | 13 => 4,
+            _ => continue,
+        };
+        if !(1..=64).contains(&count) {
+            continue;
+        }
+        let values = if count * size <= 4 {
+            (0..count as usize)
+                .map(|i| {
+                    if size == 2 {
+                        u16_at(8 + 2 * i).into()
+                    } else {
+                        u32_at(8)
+                    }
+                })
+                .collect()
+        } else {
+            let at = u64::from(u32_at(8));
+            (0..u64::from(count))
+                .map(|i| {
+                    if size == 2 {
+                        read_u16_at(r, at + 2 * i, big_endian).map(u32::from)
+                    } else {
+                        read_u32_at(r, at + 4 * i, big_endian)
+                    }
+                })
+                .collect::<io::Result<_>>()?
+        };
+        entries.push(IfdEntry { tag, values });
+    }
+    Ok((entries, next))
+}
+
+fn read_u16<R: Read>(r: &mut R, big_endian: bool) -> io::Result<u16> {
+    let mut buf = [0; 2];
+    r.read_exact(&mut buf)?;
+    Ok(if big_endian {
+        u16::from_be_bytes(buf)
+    } else {
+        u16::from_le_bytes(buf)
+    })
+}
+
+fn read_u32<R: Read>(r: &mut R, big_endian: bool) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    Ok(if big_endian {
+        u32::from_be_bytes(buf)
+    } else {
+        u32::from_le_bytes(buf)
+    })
+}
+
+fn read_u16_at<R: Read + Seek>(r: &mut R, offset: u64, big_endian: bool) -> io::Result<u16> {
+    r.seek(SeekFrom::Start(offset))?;
+    read_u16(r, big_endian)
+}
+
+fn read_u32_at<R: Read + Seek>(r: &mut R, offset: u64, big_endian: bool) -> io::Result<u32> {
+    r.seek(SeekFrom::Start(offset))?;
+    read_u32(r, big_endian)
+}
+
+/// Walks the marker segments at the start of the preview JPEG, calling
+/// `visit` with each marker and the length of its payload, with the reader
+/// positioned at the payload. Stops when `visit` returns a value or at the
+/// start of the image data.
+fn walk_jpeg<R, T, F>(r: &mut R, preview: Preview, mut visit: F) -> Option<T>
+where
+    R: Read + Seek,
+    F: FnMut(&mut R, u8, u64) -> Option<T>,
+{
+    let end = preview.offset.checked_add(preview.len)?;
+    r.seek(SeekFrom::Start(preview.offset)).ok()?;
+    if read_u16(r, true).ok()? != 0xffd8 {
+        return None;
+    }
+    let mut pos = preview.offset + 2;
+    while pos + 4 <= end {
+        r.seek(SeekFrom::Start(pos)).ok()?;
+        let marker = read_u16(r, true).ok()?;
+        if marker >> 8 != 0xff {
+            return None;
+        }
+        let marker = (marker & 0xff) as u8;
+        if marker == 0xda || marker == 0xd9 {
+            return None;
+        }
+        let len = u64::from(read_u16(r, true).ok()?);
+        if len < 2 || pos + 2 + len > end {
+            return None;
+        }
+        if let Some(result) = visit(r, marker, len - 2) {
+            return Some(result);
+        }
+        pos += 2 + len;
+    }
+    None
+}
+
+/// Returns the pixel size of a baseline or progressive JPEG.
+fn jpeg_dimensions<R: Read + Seek>(r: &mut R, preview: Preview) -> Option<(u32, u32)> {
+    let mut supported = true;
+    let size = walk_jpeg(r, preview, |r, marker, _| {
+        match marker {
+            0xc0..=0xc2 => {}
+            // Other frame types, e.g. lossless JPEG.
+            0xc3 | 0xc5..=0xc7 | 0xc9..=0xcb | 0xcd..=0xcf => {
+                supported = false;
+                return Some((0, 0));
+            }
+            _ => return None,
+        }
+        let mut frame = [0; 5];
+        r.read_exact(&mut frame).ok()?;
+        let height = u16::from_be_bytes([frame[1], frame[2]]);
+        let width = u16::from_be_bytes([frame[3], frame[4]]);
+        Some((width.into(), height.into()))
+    })?;
+    (supported && size.0 > 0 && size.1 > 0).then_some(size)
+}
+
+fn open(path: &Path) -> Option<(BufReader<File>, Preview)> {
+    let mut r = BufReader::new(File::open(path).ok()?);
+    let preview = find_preview(&mut r)?;
+    Some((r, preview))
+}
+
+/// Returns the pixel size of the preview embedded in a RAW file.
+pub fn dimensions(path: &Path) -> Option<(u32, u32)> {
+    let (mut r, preview) = open(path)?;
+    jpeg_dimensions(&mut r, preview)
+}
+
+/// Returns the TIFF-formatted EXIF data of the preview embedded in a RAW
+/// file. RAF files keep their EXIF data only there.
+pub fn preview_exif(path: &Path) -> Option<Vec<u8>> {
+    let (mut r, preview) = open(path)?;
+    walk_jpeg(&mut r, preview, |r, marker, len| {
+        if marker != 0xe1 || len < 6 {
+            return None;
+        }
+        let mut data = vec![0; len as usize];
+        r.read_exact(&mut data).ok()?;
+        data.starts_with(b"Exif\0\0").then(|| data.split_off(6))
+    })
+}
+
+/// Copies the preview embedded in `original` to `dest`. The preview is
+/// written to a temporary file that is renamed into place once complete, as
+/// a partly written preview would otherwise look fresh and be kept.
+fn extract(original: &Path, dest: &Path) -> io::Result<()> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let (mut r, preview) =
+        open(original).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no preview"))?;
+    r.seek(SeekFrom::Start(preview.offset))?;
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut tmp = dest.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}-{}.tmp",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp = PathBuf::from(tmp);
+    let result = File::create(&tmp).and_then(|mut out| {
+        let copied = io::copy(&mut r.take(preview.len), &mut out)?;
+        if copied < preview.len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        out.sync_all()?;
+        std::fs::rename(&tmp, dest)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Returns the path of the cached preview of a RAW file, extracting it first
+/// if needed.
+pub fn ensure_preview(
+    cache_dir: &Path,
+    album: &str,
+    original: &Path,
+    filename: &str,
+) -> io::Result<PathBuf> {
+    let path = cache_dir
+        .join(album)
+        .join("preview")
+        .join(preview_name(filename));
+    if !path.is_file() || is_stale(original, &path) {
+        extract(original, &path)?;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    pub fn fixture_jpeg() -> Vec<u8> {
+        std::fs::read(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/DSCF0199.jpg"))
+            .unwrap()
+    }
+
+    /// Builds a minimal RAF file around the given JPEG.
+    pub fn make_raf(jpeg: &[u8]) -> Vec<u8> {
+        let mut raf = RAF_MAGIC.to_vec();
+        raf.resize(RAF_JPEG_POINTER as usize, 0);
+        raf.extend_from_slice(&100u32.to_be_bytes());
+        raf.extend_from_slice(&(jpeg.len() as u32).to_be_bytes());
+        raf.resize(100, 0);
+        raf.extend_from_slice(jpeg);
+        raf
+    }
+
+    fn ifd_entry(tag: u16, value: u32) -> Vec<u8> {
+        let mut entry = tag.to_le_bytes().to_vec();
+        entry.extend_from_slice(&4u16.to_le_bytes());
+        entry.extend_from_slice(&1u32.to_le_bytes());
+        entry.extend_from_slice(&value.to_le_bytes());
+        entry
+    }
+
+    /// Builds a little-endian TIFF with the JPEG as a preview in IFD0, like
+    /// ARW files have it, and a lossless "JPEG" strip in a SubIFD, like the
+    /// raw data of DNG files.
+    fn make_tiff(jpeg: &[u8]) -> Vec<u8> {
+        let lossless = [0xff, 0xd8, 0xff, 0xc3, 0x00, 0x0b, 8, 0, 16, 0, 16, 1, 0, 0];
+        let ifd0 = 8u32;
+        let sub_ifd = ifd0 + 2 + 3 * 12 + 4;
+        let lossless_at = sub_ifd + 2 + 4 * 12 + 4;
+        let jpeg_at = lossless_at + lossless.len() as u32;
+
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend_from_slice(&ifd0.to_le_bytes());
+        tiff.extend_from_slice(&3u16.to_le_bytes());
+        tiff.extend(ifd_entry(TAG_SUB_IFDS, sub_ifd));
+        tiff.extend(ifd_entry(TAG_JPEG_OFFSET, jpeg_at));
+        tiff.extend(ifd_entry(TAG_JPEG_LENGTH, jpeg.len() as u32));
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff.extend_from_slice(&4u16.to_le_bytes());
+        tiff.extend(ifd_entry(TAG_COMPRESSION, 7));
+        tiff.extend(ifd_entry(TAG_STRIP_OFFSETS, lossless_at));
+        tiff.extend(ifd_entry(TAG_STRIP_BYTE_COUNTS, lossless.len() as u32));
+        tiff.extend(ifd_entry(TAG_SUB_IFDS, ifd0));
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff.extend_from_slice(&lossless);
+        tiff.extend_from_slice(jpeg);
+        tiff
+    }
+
+    #[test]
+    fn find_preview_in_raf() {
+        let jpeg = fixture_jpeg();
+        let preview = find_preview(&mut Cursor::new(make_raf(&jpeg))).unwrap();
+        assert_eq!(
+            preview,
+            Preview {
+                offset: 100,
+                len: jpeg.len() as u64
+            }
+        );
+    }
+
+    #[test]
+    fn find_preview_in_tiff_skips_lossless() {
+        let jpeg = fixture_jpeg();
+        let tiff = make_tiff(&jpeg);
+        let preview = find_preview(&mut Cursor::new(&tiff)).unwrap();
+        assert_eq!(preview.len, jpeg.len() as u64);
+        assert_eq!(&tiff[preview.offset as usize..], &jpeg[..]);
+    }
+
+    #[test]
+    fn find_preview_not_raw() {
+        assert!(find_preview(&mut Cursor::new(fixture_jpeg())).is_none());
+        assert!(find_preview(&mut Cursor::new(b"short")).is_none());
+    }
+
+    #[test]
+    fn find_preview_raf_with_bad_pointer() {
+        let mut raf = make_raf(&fixture_jpeg());
+        raf[RAF_JPEG_POINTER as usize + 3] = 99;
+        assert!(find_preview(&mut Cursor::new(raf)).is_none());
+    }
+
+    #[test]
+    fn dimensions_and_exif_of_preview() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("photo.RAF");
+        std::fs::write(&path, make_raf(&fixture_jpeg())).unwrap();
+
+        let expected = image::image_dimensions(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/DSCF0199.jpg"),
+        )
+        .unwrap();
+        assert_eq!(dimensions(&path), Some(expected));
+        assert!(preview_exif(&path).unwrap().starts_with(b"II*\0"));
+    }
+
+    #[test]
+    fn ensure_preview_extracts_jpeg() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("photo.dng");
+        let jpeg = fixture_jpeg();
+        std::fs::write(&original, make_tiff(&jpeg)).unwrap();
+
+        let cache_dir = dir.path().join("cache");
+        let path = ensure_preview(&cache_dir, "album", &original, "photo.dng").unwrap();
+        assert_eq!(path, cache_dir.join("album/preview/photo.dng.jpg"));
+        assert_eq!(std::fs::read(path).unwrap(), jpeg);
+    }
+
+    #[test]
+    fn ensure_preview_leaves_no_partial_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("photo.raf");
+        let jpeg = fixture_jpeg();
+        // The header promises more of the preview than the file holds.
+        let raf = make_raf(&jpeg);
+        std::fs::write(&original, &raf[..raf.len() - 1000]).unwrap();
+
+        let cache_dir = dir.path().join("cache");
+        assert!(ensure_preview(&cache_dir, "album", &original, "photo.raf").is_err());
+        let preview_dir = cache_dir.join("album/preview");
+        if preview_dir.is_dir() {
+            assert_eq!(std::fs::read_dir(preview_dir).unwrap().count(), 0);
+        }
+    }
+
+    #[test]
+    fn is_raw_by_extension() {
+        assert!(is_raw(Path::new("DSCF0001.RAF")));
+        assert!(is_raw(Path::new("img.nef")));
+        assert!(!is_raw(Path::new("DSCF0001.JPG")));
+        assert!(!is_raw(Path::new("raf")));
+    }
+}
@@ -0,0 +1,327 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! Removes the GPS position from the originals of albums kept off the map
+//! with `location = false`, so that the files don't give away what the map
+//! leaves out. The scrubbed copies are cached like the RAW previews.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::is_stale;
+
+/// TIFF tag of the pointer to the GPS IFD.
+const GPS_IFD: u16 = 0x8825;
+/// TIFF tag of an XMP packet, which may repeat the position.
+const XMP: u16 = 700;
+
+/// Returns the path of the scrubbed copy of `source`, writing it first if
+/// needed.
+pub fn ensure_scrubbed(cache_dir: &Path, album: &str, source: &Path) -> io::Result<PathBuf> {
+    let name = source.file_name().ok_or(io::ErrorKind::InvalidInput)?;
+    let path = cache_dir.join(album).join("scrubbed").join(name);
+    if !path.is_file() || is_stale(source, &path) {
+        let data = scrub(std::fs::read(source)?).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "can't remove the metadata")
+        })?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, data)?;
+    }
+    Ok(path)
+}
+
+/// Returns the image without its GPS position and XMP metadata. Returns
+/// `None` for files that can't be parsed and formats whose metadata isn't
+/// understood, such as AVIF, rather than risk leaving the position in.
+pub fn scrub(mut data: Vec<u8>) -> Option<Vec<u8>> {
+    match data.get(..4)? {
+        [0xFF, 0xD8, ..] => scrub_jpeg(&data),
+        b"MM\0*" | b"II*\0" => {
+            scrub_tiff(&mut data)?;
+            Some(data)
+        }
+        [0x89, b'P', b'N', b'G'] => scrub_png(&data),
+        b"RIFF" => scrub_webp(&data),
+        // GIFs don't carry EXIF data.
+        b"GIF8" => Some(data),
+        _ => None,
+    }
+}
+
+/// Empties the GPS IFD of the EXIF segment and drops the XMP segments.
+fn scrub_jpeg(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = data[..2].to_vec();
+    let mut i = 2;
+    loop {
+        if *data.get(i)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(i + 1)?;
+        // The rest is image data.
+        if matches!(marker, 0xDA | 0xD9) {
+            out.extend_from_slice(&data[i..]);
+            return Some(out);
+        }
+        let length = u16::from_be_bytes([*data.get(i + 2)?, *data.get(i + 3)?]) as usize;
+        let segment = data.get(i..i + 2 + length)?;
+        i += 2 + length;
+        let payload = segment.get(4..)?;
+        if marker == 0xE1 && payload.starts_with(b"Exif\0\0") {
+            let mut segment = segment.to_vec();
+            scrub_tiff(&mut segment[10..])?;
+            out.extend_from_slice(&segment);
+        } else if marker == 0xE1 && payload.starts_with(b"http://ns.adobe.com/") {
+            continue;
+        } else {
+            out.extend_from_slice(segment);
+        }
+    }
+}
+
+/// Empties the GPS IFD of a TIFF structure in place by zeroing its entries
+/// and their values, and blanks the XMP packet of the first IFD. Nothing
+/// moves, so all other offsets stay valid.
+fn scrub_tiff(tiff: &mut [u8]) -> Option<()> {
+    let big_endian = tiff.starts_with(b"MM");
+    let read = |tiff: &[u8], at: usize, len: usize| -> Option<usize> {
+        let bytes = tiff.get(at..at + len)?;
+        let fold = |n: usize, b: &u8| (n << 8) | *b as usize;
+        Some(if big_endian {
+            bytes.iter().fold(0, fold)
+        } else {
+            bytes.iter().rev().fold(0, fold)
+        })
+    };
+    // Returns the range of the value of the IFD entry at `entry`.
+    let value = |tiff: &[u8], entry: usize| -> Option<std::ops::Range<usize>> {
+        let size = match read(tiff, entry + 2, 2)? {
+            1 | 2 | 6 | 7 => 1,
+            3 | 8 => 2,
+            4 | 9 | 11 => 4,
+            5 | 10 | 12 => 8,
+            _ => 0,
+        } * read(tiff, entry + 4, 4)?;
+        let start = if size <= 4 {
+            entry + 8
+        } else {
+            read(tiff, entry + 8, 4)?
+        };
+        (start + size <= tiff.len()).then_some(start..start + size)
+    };
+
+    let ifd0 = read(tiff, 4, 4)?;
+    let mut gps = None;
+    for i in 0..read(tiff, ifd0, 2)? {
+        let entry = ifd0 + 2 + 12 * i;
+        match read(tiff, entry, 2)? as u16 {
+            GPS_IFD => gps = Some(read(tiff, entry + 8, 4)?),
+            XMP => {
+                let range = value(tiff, entry)?;
+                tiff[range].fill(b' ');
+            }
+            _ => {}
+        }
+    }
+    let Some(gps) = gps else {
+        return Some(());
+    };
+    let count = read(tiff, gps, 2)?;
+    for i in 0..count {
+        let range = value(tiff, gps + 2 + 12 * i)?;
+        tiff[range].fill(0);
+    }
+    // A zero entry count and next IFD offset leave an empty IFD.
+    tiff.get_mut(gps..gps + 2 + 12 * count + 4)?.fill(0);
+    Some(())
+}
+
+/// Drops the `eXIf` chunk and the XMP text chunk. Chunks have their own
+/// checksums, so the others are copied as they are.
+fn scrub_png(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = data.get(..8)?.to_vec();
+    let mut i = 8;
+    while i < data.len() {
+        let length = u32::from_be_bytes(data.get(i..i + 4)?.try_into().ok()?) as usize;
+        let chunk = data.get(i..i + 12 + length)?;
+        i += 12 + length;
+        let kind = &chunk[4..8];
+        let xmp = kind == b"iTXt" && chunk[8..].starts_with(b"XML:com.adobe.xmp\0");
+        if kind != b"eXIf" && !xmp {
+            out.extend_from_slice(chunk);
+        }
+    }
+    Some(out)
+}
+
+/// Drops the `EXIF` and `XMP ` chunks and their flags in the `VP8X` chunk.
+fn scrub_webp(data: &[u8]) -> Option<Vec<u8>> {
+    if data.get(8..12)? != b"WEBP" {
+        return None;
+    }
+    let mut out = data[..12].to_vec();
+    let mut i = 12;
+    while i < data.len() {
+        let size = u32::from_le_bytes(data.get(i + 4..i + 8)?.try_into().ok()?) as usize;
+        let end = (i + 8 + size.next_multiple_of(2)).min(data.len());
+        let chunk = data.get(i..end)?;
+        i = end;
+        match &chunk[..4] {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" => {
+                let start = out.len();
+                out.extend_from_slice(chunk);
+                // The flags of the EXIF and XMP chunks.
+                *out.get_mut(start + 8)? &= !0x0C;
+            }
+            _ => out.extend_from_slice(chunk),
+        }
+    }
+    let riff_size = u32::try_from(out.len() - 8).ok()?;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exif::{Field, In, Rational, Tag, Value};
+
+    /// An EXIF TIFF structure with a camera model and a GPS position.
+    fn tiff() -> Vec<u8> {
+        let field = |tag, value| Field {
+            tag,
+            ifd_num: In::PRIMARY,
+            value,
+        };
+        let fields = [
+            field(Tag::Model, Value::Ascii(vec![b"X-T5".to_vec()])),
+            field(Tag::GPSLatitudeRef, Value::Ascii(vec![b"N".to_vec()])),
+            field(
+                Tag::GPSLatitude,
+                Value::Rational(vec![Rational::from((60, 1)); 3]),
+            ),
+            field(Tag::GPSLongitudeRef, Value::Ascii(vec![b"E".to_vec()])),
+            field(
+                Tag::GPSLongitude,
+                Value::Rational(vec![Rational::from((24, 1)); 3]),
+            ),
+        ];
+        let mut writer = exif::experimental::Writer::new();
+        for f in &fields {
+            writer.push_field(f);
+        }
+        let mut buf = std::io::Cursor::new(Vec::new());
+        writer.write(&mut buf, false).unwrap();
+        buf.into_inner()
+    }
+
+    fn jpeg() -> Vec<u8> {
+        let mut encoded = Vec::new();
+        image::RgbImage::new(8, 8)
+            .write_to(
+                &mut std::io::Cursor::new(&mut encoded),
+                image::ImageFormat::Jpeg,
+            )
+            .unwrap();
+        let tiff = tiff();
+        let xmp = b"http://ns.adobe.com/xap/1.0/\0<x:xmpmeta exif:GPSLatitude=\"60,0N\"/>";
+        let mut out = encoded[..2].to_vec();
+        for (header, payload) in [(&b"Exif\0\0"[..], &tiff[..]), (&b""[..], &xmp[..])] {
+            out.extend_from_slice(&[0xFF, 0xE1]);
+            out.extend_from_slice(&((header.len() + payload.len() + 2) as u16).to_be_bytes());
+            out.extend_from_slice(header);
+            out.extend_from_slice(payload);
+        }
+        out.extend_from_slice(&encoded[2..]);
+        out
+    }
+
+    fn has_position(exif: &exif::Exif) -> bool {
+        exif.get_field(Tag::GPSLatitude, In::PRIMARY).is_some()
+    }
+
+    #[test]
+    fn scrub_tiff_keeps_other_fields() {
+        let mut data = tiff();
+        assert!(has_position(
+            &exif::Reader::new().read_raw(data.clone()).unwrap()
+        ));
+        scrub_tiff(&mut data).unwrap();
+        let exif = exif::Reader::new().read_raw(data).unwrap();
+        assert!(!has_position(&exif));
+        assert!(exif.get_field(Tag::Model, In::PRIMARY).is_some());
+    }
+
+    #[test]
+    fn scrub_jpeg_drops_gps_and_xmp() {
+        let original = jpeg();
+        let scrubbed = scrub(original.clone()).unwrap();
+        let read = |data: &[u8]| {
+            exif::Reader::new()
+                .read_from_container(&mut std::io::Cursor::new(data))
+                .unwrap()
+        };
+        assert!(has_position(&read(&original)));
+        assert!(!has_position(&read(&scrubbed)));
+        assert!(!scrubbed.windows(11).any(|w| w == b"GPSLatitude"));
+        assert!(image::load_from_memory(&scrubbed).is_ok());
+    }
+
+    #[test]
+    fn scrub_png_drops_exif_chunk() {
+        let mut encoded = Vec::new();
+        image::RgbImage::new(4, 4)
+            .write_to(
+                &mut std::io::Cursor::new(&mut encoded),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        let tiff = tiff();
+        // Before IEND, the last chunk.
+        let iend = encoded.len() - 12;
+        let mut data = encoded[..iend].to_vec();
+        data.extend_from_slice(&(tiff.len() as u32).to_be_bytes());
+        data.extend_from_slice(b"eXIf");
+        data.extend_from_slice(&tiff);
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&encoded[iend..]);
+
+        assert_eq!(scrub(data).unwrap(), encoded);
+    }
+
+    #[test]
+    fn scrub_webp_drops_exif_chunk() {
+        let chunk = |kind: &[u8], data: &[u8]| {
+            let mut out = kind.to_vec();
+            out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            out.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                out.push(0);
+            }
+            out
+        };
+        let vp8x = |flags: u8| chunk(b"VP8X", &[flags, 0, 0, 0, 7, 0, 0, 7, 0, 0]);
+        let riff = |chunks: &[Vec<u8>]| {
+            let body = chunks.concat();
+            let mut out = b"RIFF".to_vec();
+            out.extend_from_slice(&(body.len() as u32 + 4).to_le_bytes());
+            out.extend_from_slice(b"WEBP");
+            out.extend_from_slice(&body);
+            out
+        };
+        let image = chunk(b"VP8L", &[0x2F, 1, 2]);
+        let data = riff(&[vp8x(0x08), image.clone(), chunk(b"EXIF", &tiff())]);
+        assert_eq!(scrub(data).unwrap(), riff(&[vp8x(0), image]));
+    }
+
+    #[test]
+    fn unknown_formats_are_refused() {
+        assert!(scrub(b"GIF89a...".to_vec()).is_some());
+        assert!(scrub(b"\0\0\0\x1cftypavif".to_vec()).is_none());
+        // A truncated JPEG.
+        assert!(scrub(jpeg()[..30].to_vec()).is_none());
+    }
+}
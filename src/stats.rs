@@ -0,0 +1,333 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! Gear statistics aggregated from the EXIF data of every photo, shown as
+//! bar charts on the `/stats` page.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::exif::{camera_name, exif_field, format_year_month, read_exif};
+use crate::i18n::Language;
+use crate::timespan::Date;
+
+/// Height of a bar chart row in pixels.
+const ROW_HEIGHT: usize = 24;
+/// Share of the chart width reserved for the labels, in percent.
+const LABEL_WIDTH: f64 = 35.0;
+/// Share of the chart width that the longest bar takes, in percent. The rest
+/// is left for the count after the bar.
+const BAR_WIDTH: f64 = 55.0;
+
+/// Focal length ranges in millimeters: lower bound and label.
+const FOCAL_LENGTH_BUCKETS: &[(f64, &str)] = &[
+    (0.0, "< 16 mm"),
+    (16.0, "16–23 mm"),
+    (24.0, "24–34 mm"),
+    (35.0, "35–49 mm"),
+    (50.0, "50–84 mm"),
+    (85.0, "85–134 mm"),
+    (135.0, "135–199 mm"),
+    (200.0, "≥ 200 mm"),
+];
+
+#[derive(Serialize)]
+pub struct Bar {
+    pub label: String,
+    pub count: usize,
+    /// Length of the bar as a percentage of the chart width.
+    pub width: f64,
+    /// Position of the count after the bar, as a percentage of the chart
+    /// width.
+    pub count_x: f64,
+    /// Vertical position of the row in pixels.
+    pub y: usize,
+}
+
+#[derive(Serialize)]
+pub struct Chart {
+    pub bars: Vec<Bar>,
+    /// Height of the chart in pixels.
+    pub height: usize,
+    /// Where the labels end and the bars start, as a percentage of the chart
+    /// width.
+    pub label_x: f64,
+}
+
+impl Chart {
+    fn new(counts: Vec<(String, usize)>) -> Chart {
+        let max = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+        let bars = counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, (label, count))| {
+                let width = if max > 0 {
+                    count as f64 / max as f64 * BAR_WIDTH
+                } else {
+                    0.0
+                };
+                Bar {
+                    label,
+                    count,
+                    width,
+                    count_x: LABEL_WIDTH + width + 1.0,
+                    y: i * ROW_HEIGHT,
+                }
+            })
+            .collect::<Vec<_>>();
+        Chart {
+            height: bars.len() * ROW_HEIGHT,
+            bars,
+            label_x: LABEL_WIDTH,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bars.is_empty()
+    }
+}
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub photos: usize,
+    pub cameras: Chart,
+    pub lenses: Chart,
+    pub focal_lengths: Chart,
+    pub apertures: Chart,
+    pub isos: Chart,
+    pub months: Chart,
+}
+
+/// The values that the statistics are built from, for one photo.
+#[derive(Default)]
+struct Sample {
+    camera: Option<String>,
+    lens: Option<String>,
+    focal_length: Option<f64>,
+    aperture: Option<f64>,
+    iso: Option<u32>,
+    /// Year and month of capture.
+    month: Option<(i32, u32)>,
+}
+
+fn rational_field(exif: &exif::Exif, tag: exif::Tag) -> Option<f64> {
+    match &exif.get_field(tag, exif::In::PRIMARY)?.value {
+        exif::Value::Rational(v) => v.first().map(|r| r.to_f64()),
+        _ => None,
+    }
+}
+
+fn read_sample(path: &Path) -> Sample {
+    let Some(exif) = read_exif(path) else {
+        return Sample::default();
+    };
+    Sample {
+        camera: camera_name(
+            exif_field(&exif, exif::Tag::Make),
+            exif_field(&exif, exif::Tag::Model),
+        ),
+        lens: exif_field(&exif, exif::Tag::LensModel),
+        focal_length: rational_field(&exif, exif::Tag::FocalLength).filter(|f| *f > 0.0),
+        aperture: rational_field(&exif, exif::Tag::FNumber).filter(|f| *f > 0.0),
+        iso: exif
+            .get_field(exif::Tag::PhotographicSensitivity, exif::In::PRIMARY)
+            .and_then(|field| field.value.get_uint(0)),
+        month: exif_field(&exif, exif::Tag::DateTimeOriginal)
+            .and_then(|date| Date::parse(&date))
+            .map(|date| (date.year, date.month)),
+    }
+}
+
+/// Counts the values, most common first, ties in alphabetical order.
+fn by_count(values: impl Iterator<Item = String>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Counts the values in ascending order of their keys.
+fn by_key<K: Ord>(values: impl Iterator<Item = (K, String)>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(K, String, usize)> = Vec::new();
+    for (key, label) in values {
+        match counts.iter_mut().find(|(_, l, _)| *l == label) {
+            Some((_, _, count)) => *count += 1,
+            None => counts.push((key, label, 1)),
+        }
+    }
+    counts.sort_by(|a, b| a.0.cmp(&b.0));
+    counts
+        .into_iter()
+        .map(|(_, label, count)| (label, count))
+        .collect()
+}
+
+fn focal_length_bucket(focal_length: f64) -> usize {
+    FOCAL_LENGTH_BUCKETS
+        .iter()
+        .rposition(|(min, _)| focal_length >= *min)
+        .unwrap_or(0)
+}
+
+/// Formats an f-number the way it's usually written, e.g. "ƒ/2.8" or "ƒ/8".
+fn format_aperture(f_number: f64) -> String {
+    let rounded = (f_number * 10.0).round() / 10.0;
+    format!("\u{192}/{}", rounded)
+}
+
+/// Counts photos per month from the first to the last month, including the
+/// months without photos.
+fn months(samples: &[Sample], lang: Language) -> Vec<(String, usize)> {
+    let mut months: Vec<(i32, u32)> = samples.iter().filter_map(|s| s.month).collect();
+    months.sort();
+    let (Some(&first), Some(&last)) = (months.first(), months.last()) else {
+        return Vec::new();
+    };
+
+    let mut counts = Vec::new();
+    let (mut year, mut month) = first;
+    while (year, month) <= last {
+        let count = months.iter().filter(|&&m| m == (year, month)).count();
+        let label = format_year_month(&format!("{}-{:02}", year, month), lang);
+        counts.push((label, count));
+        (year, month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+    }
+    counts
+}
+
+/// Reads the EXIF data of the given photos and aggregates it.
+pub fn collect(photos: &[PathBuf], lang: Language) -> Stats {
+    let samples: Vec<Sample> = photos.iter().map(|p| read_sample(p)).collect();
+    Stats {
+        photos: samples.len(),
+        cameras: Chart::new(by_count(samples.iter().filter_map(|s| s.camera.clone()))),
+        lenses: Chart::new(by_count(samples.iter().filter_map(|s| s.lens.clone()))),
+        focal_lengths: Chart::new(by_key(samples.iter().filter_map(|s| {
+            let bucket = focal_length_bucket(s.focal_length?);
+            Some((bucket, FOCAL_LENGTH_BUCKETS[bucket].1.to_string()))
+        }))),
+        apertures: Chart::new(by_key(samples.iter().filter_map(|s| {
+            let f_number = s.aperture?;
+            // Sort by tenths of a stop number, as floats can't be ordered.
+            Some(((f_number * 10.0).round() as u32, format_aperture(f_number)))
+        }))),
+        isos: Chart::new(by_key(
+            samples
+                .iter()
+                .filter_map(|s| Some((s.iso?, format!("ISO {}", s.iso?)))),
+        )),
+        months: Chart::new(months(&samples, lang)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    fn labels(chart: &Chart) -> Vec<(&str, usize)> {
+        chart
+            .bars
+            .iter()
+            .map(|bar| (bar.label.as_str(), bar.count))
+            .collect()
+    }
+
+    #[test]
+    fn collect_from_fixtures() {
+        let photos = vec![
+            fixture("DSCF0199.jpg"),
+            fixture("DSCF0199.jpg"),
+            fixture("DSCF0263.jpg"),
+            PathBuf::from("/nonexistent/photo.jpg"),
+        ];
+        let stats = collect(&photos, Language::En);
+
+        assert_eq!(stats.photos, 4);
+        assert_eq!(labels(&stats.cameras), vec![("FUJIFILM X-T5", 3)]);
+        assert_eq!(labels(&stats.focal_lengths)[0], ("16–23 mm", 2));
+        assert_eq!(labels(&stats.apertures)[0].0, "\u{192}/5.6");
+        assert_eq!(
+            labels(&stats.months),
+            vec![("February 2026", 2), ("March 2026", 1)]
+        );
+    }
+
+    #[test]
+    fn chart_scales_to_longest_bar() {
+        let chart = Chart::new(vec![("a".to_string(), 4), ("b".to_string(), 1)]);
+        assert_eq!(chart.height, 2 * ROW_HEIGHT);
+        assert_eq!(chart.bars[0].width, BAR_WIDTH);
+        assert_eq!(chart.bars[1].width, BAR_WIDTH / 4.0);
+        assert_eq!(chart.bars[1].y, ROW_HEIGHT);
+    }
+
+    #[test]
+    fn by_count_orders_by_frequency() {
+        let values = ["b", "a", "b", "c", "a", "b"].map(String::from);
+        assert_eq!(
+            by_count(values.into_iter()),
+            vec![
+                ("b".to_string(), 3),
+                ("a".to_string(), 2),
+                ("c".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn focal_length_buckets() {
+        assert_eq!(FOCAL_LENGTH_BUCKETS[focal_length_bucket(8.0)].1, "< 16 mm");
+        assert_eq!(
+            FOCAL_LENGTH_BUCKETS[focal_length_bucket(23.9)].1,
+            "16–23 mm"
+        );
+        assert_eq!(
+            FOCAL_LENGTH_BUCKETS[focal_length_bucket(50.0)].1,
+            "50–84 mm"
+        );
+        assert_eq!(
+            FOCAL_LENGTH_BUCKETS[focal_length_bucket(600.0)].1,
+            "≥ 200 mm"
+        );
+    }
+
+    #[test]
+    fn aperture_formatting() {
+        assert_eq!(format_aperture(2.8), "\u{192}/2.8");
+        assert_eq!(format_aperture(8.0), "\u{192}/8");
+        assert_eq!(format_aperture(5.599), "\u{192}/5.6");
+    }
+
+    #[test]
+    fn months_fill_gaps() {
+        let samples = [(2025, 11), (2026, 2), (2025, 11)].map(|month| Sample {
+            month: Some(month),
+            ..Default::default()
+        });
+        assert_eq!(
+            months(&samples, Language::En),
+            vec![
+                ("November 2025".to_string(), 2),
+                ("December 2025".to_string(), 0),
+                ("January 2026".to_string(), 0),
+                ("February 2026".to_string(), 1),
+            ]
+        );
+    }
+}
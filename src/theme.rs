@@ -0,0 +1,158 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use minijinja::{Environment, Value};
+use serde::{Serialize, Serializer};
+
+use crate::assets::StaticAssets;
+use crate::exif::ExifInfo;
+
+/// User-supplied templates loaded at runtime from `theme/templates/`.
+///
+/// Templates are rendered with MiniJinja and receive the same context as the
+/// built-in askama templates. Pages without a matching file in the theme
+/// directory fall back to the built-in templates.
+pub struct Theme {
+    dir: PathBuf,
+    env: Environment<'static>,
+}
+
+impl Theme {
+    /// Returns `None` if the theme template directory doesn't exist.
+    pub fn load(dir: &Path, assets: Arc<StaticAssets>) -> Option<Theme> {
+        if !dir.is_dir() {
+            return None;
+        }
+
+        let mut env = Environment::new();
+        env.set_loader(minijinja::path_loader(dir));
+        env.add_function("static_url", move |name: &str| {
+            Value::from_safe_string(assets.url(name))
+        });
+
+        Some(Theme {
+            dir: dir.to_path_buf(),
+            env,
+        })
+    }
+
+    /// Renders `name` if the theme overrides it, otherwise returns `None`.
+    pub fn render<S: Serialize>(
+        &self,
+        name: &str,
+        ctx: &S,
+    ) -> Option<Result<String, minijinja::Error>> {
+        if !self.dir.join(name).is_file() {
+            return None;
+        }
+        Some(
+            self.env
+                .get_template(name)
+                .and_then(|tmpl| tmpl.render(ctx)),
+        )
+    }
+}
+
+/// Serializes `ExifInfo` together with its summary line, since templates
+/// can't call methods on the context.
+pub fn serialize_exif<S: Serializer>(exif: &ExifInfo, serializer: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct ExifContext<'a> {
+        #[serde(flatten)]
+        info: &'a ExifInfo,
+        summary: String,
+    }
+
+    ExifContext {
+        info: exif,
+        summary: exif.summary(),
+    }
+    .serialize(serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use std::fs;
+
+    #[derive(Serialize)]
+    struct Context {
+        site_title: String,
+        #[serde(serialize_with = "serialize_exif")]
+        exif: ExifInfo,
+        details: Vec<(&'static str, String)>,
+    }
+
+    fn context() -> Context {
+        let exif = ExifInfo {
+            camera: Some("FUJIFILM X-T5".to_string()),
+            iso: Some("125".to_string()),
+            ..Default::default()
+        };
+        Context {
+            site_title: "Kuvasivu".to_string(),
+            details: exif.details(Language::En.strings()),
+            exif,
+        }
+    }
+
+    #[test]
+    fn load_missing_dir() {
+        let assets = Arc::new(StaticAssets::default());
+        assert!(Theme::load(Path::new("/nonexistent/theme"), assets).is_none());
+    }
+
+    #[test]
+    fn render_missing_template_falls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let theme = Theme::load(dir.path(), Arc::new(StaticAssets::default())).unwrap();
+        assert!(theme.render("index.html", &context()).is_none());
+    }
+
+    #[test]
+    fn render_with_context() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("photo.html"),
+            "{{ site_title }}|{{ exif.camera }}|{{ exif.summary }}|{{ static_url('x.css') }}|{% for label, value in details %}{{ label }}={{ value }};{% endfor %}",
+        )
+        .unwrap();
+        let theme = Theme::load(dir.path(), Arc::new(StaticAssets::default())).unwrap();
+        let html = theme.render("photo.html", &context()).unwrap().unwrap();
+        assert_eq!(
+            html,
+            "Kuvasivu|FUJIFILM X-T5|FUJIFILM X-T5 · ISO 125|/static/x.css|Camera=FUJIFILM X-T5;ISO=125;"
+        );
+    }
+
+    #[test]
+    fn render_with_inheritance() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("base.html"),
+            "<title>{{ site_title }}</title>{% block content %}{% endblock %}",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("index.html"),
+            "{% extends \"base.html\" %}{% block content %}hello{% endblock %}",
+        )
+        .unwrap();
+        let theme = Theme::load(dir.path(), Arc::new(StaticAssets::default())).unwrap();
+        let html = theme.render("index.html", &context()).unwrap().unwrap();
+        assert_eq!(html, "<title>Kuvasivu</title>hello");
+    }
+
+    #[test]
+    fn render_syntax_error() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("album.html"), "{% if %}").unwrap();
+        let theme = Theme::load(dir.path(), Arc::new(StaticAssets::default())).unwrap();
+        assert!(theme.render("album.html", &context()).unwrap().is_err());
+    }
+}
@@ -0,0 +1,355 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+//! The photos of all albums grouped by their EXIF capture date, shown on the
+//! `/timeline` and `/on-this-day` pages.
+
+use serde::Serialize;
+
+use crate::Photo;
+use crate::exif::format_year_month;
+use crate::i18n::Language;
+use crate::timespan::{Date, day_month_year};
+
+/// A photo with a capture date.
+pub struct Entry {
+    /// Slug of the album that the photo is in.
+    pub album: String,
+    pub photo: Photo,
+    /// EXIF `DateTimeOriginal`, used for sorting.
+    pub taken: String,
+    pub date: Date,
+}
+
+/// A year in the timeline navigation, with the months that have photos.
+#[derive(Serialize)]
+pub struct Year {
+    pub year: i32,
+    pub url: String,
+    pub count: usize,
+    pub current: bool,
+    pub months: Vec<Month>,
+}
+
+#[derive(Serialize)]
+pub struct Month {
+    pub name: String,
+    pub url: String,
+    pub count: usize,
+    pub current: bool,
+}
+
+/// Photos taken in the same month or on the same day.
+#[derive(Serialize)]
+pub struct Group {
+    pub title: String,
+    /// Link to the page of the month, on the year page.
+    pub url: Option<String>,
+    pub photos: Vec<GroupPhoto>,
+}
+
+#[derive(Serialize)]
+pub struct GroupPhoto {
+    pub album: String,
+    pub photo: Photo,
+}
+
+#[derive(Serialize)]
+pub struct Link {
+    pub label: String,
+    pub url: String,
+}
+
+fn year_url(year: i32) -> String {
+    format!("/timeline/{}", year)
+}
+
+fn month_url(year: i32, month: u32) -> String {
+    format!("/timeline/{}/{:02}", year, month)
+}
+
+fn month_label(year: i32, month: u32, lang: Language) -> String {
+    format_year_month(&format!("{}-{:02}", year, month), lang)
+}
+
+/// Sorts the entries into chronological order.
+pub fn sort(entries: &mut [Entry]) {
+    entries.sort_by(|a, b| {
+        (&a.taken, &a.album, &a.photo.filename).cmp(&(&b.taken, &b.album, &b.photo.filename))
+    });
+}
+
+/// Builds the year and month navigation from sorted entries, newest year
+/// first. `current` is the year and optionally the month being shown.
+pub fn navigation(
+    entries: &[Entry],
+    current: Option<(i32, Option<u32>)>,
+    lang: Language,
+) -> Vec<Year> {
+    let mut years: Vec<Year> = Vec::new();
+    for entry in entries {
+        let Date { year, month, .. } = entry.date;
+        if years.last().is_none_or(|y| y.year != year) {
+            years.push(Year {
+                year,
+                url: year_url(year),
+                count: 0,
+                current: current.is_some_and(|(y, _)| y == year),
+                months: Vec::new(),
+            });
+        }
+        let y = years.last_mut().unwrap();
+        y.count += 1;
+        let name = lang.month_name(month).unwrap_or_default();
+        if y.months.last().is_none_or(|m| m.name != name) {
+            y.months.push(Month {
+                name: name.to_string(),
+                url: month_url(year, month),
+                count: 0,
+                current: current == Some((year, Some(month))),
+            });
+        }
+        y.months.last_mut().unwrap().count += 1;
+    }
+    years.reverse();
+    years
+}
+
+/// Groups the sorted entries of a year by month.
+pub fn by_month(entries: Vec<Entry>, lang: Language) -> Vec<Group> {
+    group(
+        entries,
+        |date| (date.year, date.month, 0),
+        |date| Group {
+            title: month_label(date.year, date.month, lang),
+            url: Some(month_url(date.year, date.month)),
+            photos: Vec::new(),
+        },
+    )
+}
+
+/// Groups the sorted entries of a month by day.
+pub fn by_day(entries: Vec<Entry>, lang: Language) -> Vec<Group> {
+    group(
+        entries,
+        |date| (date.year, date.month, date.day),
+        |date| Group {
+            title: day_month_year(lang, date),
+            url: None,
+            photos: Vec::new(),
+        },
+    )
+}
+
+/// Groups the sorted entries by year, newest first.
+pub fn by_year(entries: Vec<Entry>) -> Vec<Group> {
+    let mut groups = group(
+        entries,
+        |date| (date.year, 0, 0),
+        |date| Group {
+            title: date.year.to_string(),
+            url: Some(month_url(date.year, date.month)),
+            photos: Vec::new(),
+        },
+    );
+    groups.reverse();
+    groups
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns true if a photo taken on `date` was taken on the same day as
+/// `today` in an earlier year. Photos from 29 February show up on 28
+/// February when `today` is not in a leap year.
+pub fn is_on_this_day(date: Date, today: Date) -> bool {
+    if date.year >= today.year {
+        return false;
+    }
+    (date.month, date.day) == (today.month, today.day)
+        || ((date.month, date.day) == (2, 29)
+            && (today.month, today.day) == (2, 28)
+            && !is_leap_year(today.year))
+}
+
+fn group(
+    entries: Vec<Entry>,
+    key: impl Fn(Date) -> (i32, u32, u32),
+    new_group: impl Fn(Date) -> Group,
+) -> Vec<Group> {
+    let mut groups: Vec<((i32, u32, u32), Group)> = Vec::new();
+    for entry in entries {
+        let k = key(entry.date);
+        if groups.last().is_none_or(|(last, _)| *last != k) {
+            groups.push((k, new_group(entry.date)));
+        }
+        groups.last_mut().unwrap().1.photos.push(GroupPhoto {
+            album: entry.album,
+            photo: entry.photo,
+        });
+    }
+    groups.into_iter().map(|(_, group)| group).collect()
+}
+
+/// Links to the previous and next year with photos.
+pub fn adjacent_years(entries: &[Entry], year: i32) -> (Option<Link>, Option<Link>) {
+    let years = distinct(entries, |date| date.year);
+    adjacent(&years, year, |&y| Link {
+        label: y.to_string(),
+        url: year_url(y),
+    })
+}
+
+/// Links to the previous and next month with photos.
+pub fn adjacent_months(
+    entries: &[Entry],
+    year: i32,
+    month: u32,
+    lang: Language,
+) -> (Option<Link>, Option<Link>) {
+    let months = distinct(entries, |date| (date.year, date.month));
+    adjacent(&months, (year, month), |&(y, m)| Link {
+        label: month_label(y, m, lang),
+        url: month_url(y, m),
+    })
+}
+
+fn distinct<K: PartialEq>(entries: &[Entry], key: impl Fn(Date) -> K) -> Vec<K> {
+    let mut keys: Vec<K> = Vec::new();
+    for entry in entries {
+        let k = key(entry.date);
+        if keys.last() != Some(&k) {
+            keys.push(k);
+        }
+    }
+    keys
+}
+
+fn adjacent<K: PartialEq>(
+    keys: &[K],
+    current: K,
+    link: impl Fn(&K) -> Link,
+) -> (Option<Link>, Option<Link>) {
+    let Some(i) = keys.iter().position(|k| *k == current) else {
+        return (None, None);
+    };
+    let prev = i.checked_sub(1).map(|i| link(&keys[i]));
+    let next = keys.get(i + 1).map(link);
+    (prev, next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(album: &str, filename: &str, taken: &str) -> Entry {
+        Entry {
+            album: album.to_string(),
+            photo: Photo {
+                filename: filename.to_string(),
+                version: String::new(),
+                placeholder: None,
+                dimensions: None,
+                medium: None,
+                animated: false,
+            },
+            taken: taken.to_string(),
+            date: Date::parse(taken).unwrap(),
+        }
+    }
+
+    fn entries() -> Vec<Entry> {
+        let mut entries = vec![
+            entry("b", "3.jpg", "2026-02-01 15:01:00"),
+            entry("a", "1.jpg", "2025-11-12 10:00:00"),
+            entry("a", "2.jpg", "2026-02-01 09:30:00"),
+            entry("b", "4.jpg", "2026-03-08 12:00:00"),
+            entry("a", "5.jpg", "2026-02-14 18:00:00"),
+        ];
+        sort(&mut entries);
+        entries
+    }
+
+    fn filenames(group: &Group) -> Vec<&str> {
+        group
+            .photos
+            .iter()
+            .map(|p| p.photo.filename.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn navigation_newest_year_first() {
+        let years = navigation(&entries(), Some((2026, Some(2))), Language::En);
+        assert_eq!(years.len(), 2);
+        assert_eq!((years[0].year, years[0].count), (2026, 4));
+        assert!(years[0].current);
+        assert!(!years[1].current);
+
+        let months: Vec<_> = years[0]
+            .months
+            .iter()
+            .map(|m| (m.name.as_str(), m.url.as_str(), m.count, m.current))
+            .collect();
+        assert_eq!(
+            months,
+            vec![
+                ("February", "/timeline/2026/02", 3, true),
+                ("March", "/timeline/2026/03", 1, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn group_by_month_and_day() {
+        let months = by_month(entries(), Language::En);
+        let titles: Vec<_> = months.iter().map(|g| g.title.as_str()).collect();
+        assert_eq!(titles, ["November 2025", "February 2026", "March 2026"]);
+        assert_eq!(filenames(&months[1]), ["2.jpg", "3.jpg", "5.jpg"]);
+        assert_eq!(months[1].url.as_deref(), Some("/timeline/2026/02"));
+
+        let days = by_day(entries(), Language::Fi);
+        assert_eq!(days[1].title, "1.2.2026");
+        assert_eq!(filenames(&days[1]), ["2.jpg", "3.jpg"]);
+        assert_eq!(days[1].url, None);
+    }
+
+    #[test]
+    fn group_by_year_newest_first() {
+        let years = by_year(entries());
+        let titles: Vec<_> = years.iter().map(|g| g.title.as_str()).collect();
+        assert_eq!(titles, ["2026", "2025"]);
+        assert_eq!(filenames(&years[0]), ["2.jpg", "3.jpg", "5.jpg", "4.jpg"]);
+    }
+
+    #[test]
+    fn on_this_day() {
+        let date = |s| Date::parse(s).unwrap();
+        let today = date("2026-02-01");
+        assert!(is_on_this_day(date("2025-02-01"), today));
+        assert!(is_on_this_day(date("1999-02-01"), today));
+        assert!(!is_on_this_day(date("2026-02-01"), today));
+        assert!(!is_on_this_day(date("2025-02-02"), today));
+
+        assert!(is_on_this_day(date("2024-02-29"), date("2026-02-28")));
+        assert!(!is_on_this_day(date("2024-02-29"), date("2028-02-28")));
+        assert!(is_on_this_day(date("2024-02-29"), date("2028-02-29")));
+    }
+
+    #[test]
+    fn adjacent_links() {
+        let entries = entries();
+        let (prev, next) = adjacent_years(&entries, 2026);
+        assert_eq!(prev.unwrap().url, "/timeline/2025");
+        assert!(next.is_none());
+
+        let (prev, next) = adjacent_months(&entries, 2026, 2, Language::En);
+        assert_eq!(prev.unwrap().label, "November 2025");
+        assert_eq!(next.unwrap().url, "/timeline/2026/03");
+
+        let (prev, next) = adjacent_months(&entries, 2024, 1, Language::En);
+        assert!(prev.is_none() && next.is_none());
+    }
+}
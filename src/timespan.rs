@@ -0,0 +1,334 @@
+// SPDX-FileCopyrightText: 2026 Miikka Koskinen
+//
+// SPDX-License-Identifier: MIT
+
+use crate::exif::format_year_month;
+use crate::i18n::{Language, Locale};
+
+/// Ranges spanning at most this many days are shown with day precision.
+const DAY_PRECISION_MAX_DAYS: i64 = 14;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Parses the date part of an EXIF date, e.g. "2024-06-15 12:00:00" or
+    /// "2024:06:15 12:00:00".
+    pub fn parse(datetime_str: &str) -> Option<Date> {
+        let mut parts = datetime_str.split(['-', ':', ' ']);
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Days since 1970-01-01 in the proleptic Gregorian calendar.
+    pub fn days_since_epoch(&self) -> i64 {
+        // From Howard Hinnant's `days_from_civil`.
+        let y = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = i64::from(self.month);
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+}
+
+/// Formats the span between two EXIF dates, e.g. "12–14 June 2024",
+/// "28 June – 3 July 2024" or "June – September 2024".
+pub fn format_range(first: &str, last: &str, locale: &Locale) -> String {
+    if let Some(format) = &locale.timespan_format {
+        return format_custom_range(first, last, format, locale.language);
+    }
+
+    let (Some(start), Some(end)) = (Date::parse(first), Date::parse(last)) else {
+        return String::new();
+    };
+    let lang = locale.language;
+
+    if end.days_since_epoch() - start.days_since_epoch() <= DAY_PRECISION_MAX_DAYS {
+        if start == end {
+            day_month_year(lang, end)
+        } else if (start.year, start.month) == (end.year, end.month) {
+            format!("{}–{}", day(lang, start), day_month_year(lang, end))
+        } else if start.year == end.year {
+            format!(
+                "{}{}{}",
+                day_month(lang, start),
+                separator(lang),
+                day_month_year(lang, end)
+            )
+        } else {
+            format!(
+                "{}{}{}",
+                day_month_year(lang, start),
+                separator(lang),
+                day_month_year(lang, end)
+            )
+        }
+    } else if (start.year, start.month) == (end.year, end.month) {
+        format_year_month(last, lang)
+    } else if start.year == end.year {
+        format!(
+            "{}{}{}",
+            month(lang, start),
+            separator(lang),
+            format_year_month(last, lang)
+        )
+    } else {
+        format!(
+            "{}{}{}",
+            format_year_month(first, lang),
+            separator(lang),
+            format_year_month(last, lang)
+        )
+    }
+}
+
+fn separator(lang: Language) -> &'static str {
+    match lang {
+        Language::Fi => "–",
+        Language::En | Language::Sv | Language::De => " – ",
+    }
+}
+
+fn month(lang: Language, date: Date) -> &'static str {
+    lang.month_name(date.month).unwrap_or_default()
+}
+
+fn day(lang: Language, date: Date) -> String {
+    match lang {
+        Language::En | Language::Sv => date.day.to_string(),
+        Language::Fi | Language::De => format!("{}.", date.day),
+    }
+}
+
+pub fn day_month(lang: Language, date: Date) -> String {
+    match lang {
+        Language::En | Language::Sv | Language::De => {
+            format!("{} {}", day(lang, date), month(lang, date))
+        }
+        Language::Fi => format!("{}.{}.", date.day, date.month),
+    }
+}
+
+pub fn day_month_year(lang: Language, date: Date) -> String {
+    match lang {
+        Language::Fi => format!("{}.{}.{}", date.day, date.month, date.year),
+        Language::En | Language::Sv | Language::De => {
+            format!("{} {}", day_month(lang, date), date.year)
+        }
+    }
+}
+
+/// Formats both ends with `format` and joins them unless they're equal.
+fn format_custom_range(first: &str, last: &str, format: &str, lang: Language) -> String {
+    let (Some(start), Some(end)) = (Date::parse(first), Date::parse(last)) else {
+        return String::new();
+    };
+    let start = format_date(start, format, lang);
+    let end = format_date(end, format, lang);
+    if start == end {
+        end
+    } else {
+        format!("{}{}{}", start, separator(lang), end)
+    }
+}
+
+/// Formats a date with a strftime-like pattern. Supports `%Y`, `%y`, `%m`,
+/// `%-m`, `%d`, `%-d`, `%B` (month name) and `%%`; other text is copied as
+/// is.
+pub fn format_date(date: Date, format: &str, lang: Language) -> String {
+    let mut out = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let mut spec = chars.next();
+        let no_pad = spec == Some('-');
+        if no_pad {
+            spec = chars.next();
+        }
+        match (spec, no_pad) {
+            (Some('Y'), _) => out.push_str(&date.year.to_string()),
+            (Some('y'), _) => out.push_str(&format!("{:02}", date.year.rem_euclid(100))),
+            (Some('m'), false) => out.push_str(&format!("{:02}", date.month)),
+            (Some('m'), true) => out.push_str(&date.month.to_string()),
+            (Some('d'), false) => out.push_str(&format!("{:02}", date.day)),
+            (Some('d'), true) => out.push_str(&date.day.to_string()),
+            (Some('B'), _) => out.push_str(month(lang, date)),
+            (Some('%'), _) => out.push('%'),
+            (Some(other), _) => {
+                out.push('%');
+                if no_pad {
+                    out.push('-');
+                }
+                out.push(other);
+            }
+            (None, _) => out.push('%'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn en() -> Locale {
+        Locale::default()
+    }
+
+    fn locale(language: Language) -> Locale {
+        Locale {
+            language,
+            ..Default::default()
+        }
+    }
+
+    fn custom(format: &str) -> Locale {
+        Locale {
+            timespan_format: Some(format.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_date_formats() {
+        let expected = Some(Date {
+            year: 2024,
+            month: 6,
+            day: 15,
+        });
+        assert_eq!(Date::parse("2024-06-15 12:00:00"), expected);
+        assert_eq!(Date::parse("2024:06:15 12:00:00"), expected);
+        assert_eq!(Date::parse("garbage"), None);
+        assert_eq!(Date::parse("2024:13:01 00:00:00"), None);
+    }
+
+    #[test]
+    fn days_since_epoch() {
+        let day = |s| Date::parse(s).unwrap().days_since_epoch();
+        assert_eq!(day("1970-01-01"), 0);
+        assert_eq!(day("2000-03-01"), 11017);
+        assert_eq!(day("2024-03-01") - day("2024-02-28"), 2);
+    }
+
+    #[test]
+    fn single_day() {
+        assert_eq!(
+            format_range("2024:06:12 10:00:00", "2024:06:12 18:00:00", &en()),
+            "12 June 2024"
+        );
+    }
+
+    #[test]
+    fn days_in_same_month() {
+        assert_eq!(
+            format_range("2024:06:12 10:00:00", "2024:06:14 18:00:00", &en()),
+            "12–14 June 2024"
+        );
+    }
+
+    #[test]
+    fn days_across_months() {
+        assert_eq!(
+            format_range("2024:06:28 10:00:00", "2024:07:03 18:00:00", &en()),
+            "28 June – 3 July 2024"
+        );
+    }
+
+    #[test]
+    fn days_across_years() {
+        assert_eq!(
+            format_range("2023:12:30 10:00:00", "2024:01:02 18:00:00", &en()),
+            "30 December 2023 – 2 January 2024"
+        );
+    }
+
+    #[test]
+    fn months_in_same_year() {
+        assert_eq!(
+            format_range("2024:06:15 10:00:00", "2024:09:20 18:00:00", &en()),
+            "June – September 2024"
+        );
+    }
+
+    #[test]
+    fn whole_month() {
+        assert_eq!(
+            format_range("2024:06:01 10:00:00", "2024:06:30 18:00:00", &en()),
+            "June 2024"
+        );
+    }
+
+    #[test]
+    fn months_across_years() {
+        assert_eq!(
+            format_range("2023:11:15 10:00:00", "2024:02:20 18:00:00", &en()),
+            "November 2023 – February 2024"
+        );
+    }
+
+    #[test]
+    fn localized_ranges() {
+        let short = ("2024:06:12 10:00:00", "2024:06:14 18:00:00");
+        let across = ("2024:06:28 10:00:00", "2024:07:03 18:00:00");
+        let long = ("2024:06:15 10:00:00", "2024:09:20 18:00:00");
+
+        let fi = locale(Language::Fi);
+        assert_eq!(format_range(short.0, short.1, &fi), "12.–14.6.2024");
+        assert_eq!(format_range(across.0, across.1, &fi), "28.6.–3.7.2024");
+        assert_eq!(format_range(long.0, long.1, &fi), "kesäkuu–syyskuu 2024");
+
+        let sv = locale(Language::Sv);
+        assert_eq!(format_range(short.0, short.1, &sv), "12–14 juni 2024");
+        assert_eq!(format_range(long.0, long.1, &sv), "juni – september 2024");
+
+        let de = locale(Language::De);
+        assert_eq!(format_range(short.0, short.1, &de), "12.–14. Juni 2024");
+        assert_eq!(
+            format_range(across.0, across.1, &de),
+            "28. Juni – 3. Juli 2024"
+        );
+    }
+
+    #[test]
+    fn unparseable_dates() {
+        assert_eq!(format_range("garbage", "2024:06:14 18:00:00", &en()), "");
+    }
+
+    #[test]
+    fn custom_format() {
+        let locale = custom("%Y-%m");
+        assert_eq!(
+            format_range("2024:06:12 10:00:00", "2024:06:14 18:00:00", &locale),
+            "2024-06"
+        );
+        assert_eq!(
+            format_range("2024:06:12 10:00:00", "2024:09:14 18:00:00", &locale),
+            "2024-06 – 2024-09"
+        );
+    }
+
+    #[test]
+    fn format_date_specifiers() {
+        let date = Date::parse("2024:06:05 10:00:00").unwrap();
+        assert_eq!(
+            format_date(date, "%d.%m.%y %-d.%-m.%Y", Language::En),
+            "05.06.24 5.6.2024"
+        );
+        assert_eq!(format_date(date, "%B %Y", Language::Fi), "kesäkuu 2024");
+        assert_eq!(format_date(date, "100%% %q %", Language::En), "100% %q %");
+    }
+}
@@ -96,12 +96,21 @@ async fn get_bytes(router: axum::Router, uri: &str) -> (StatusCode, Vec<u8>, Str
 
 // --- Snapshot tests ---
 
+/// Redacts content fingerprints so that snapshots don't change whenever a
+/// static file or the mtime of a test photo changes.
+fn assert_page_snapshot(name: &str, body: &str) {
+    let mut settings = insta::Settings::clone_current();
+    settings.add_filter(r"\?v=[0-9a-f]{16}", "?v=[version]");
+    settings.add_filter(r"\.[0-9a-f]{16}\.", ".[hash].");
+    settings.bind(|| insta::assert_snapshot!(name, body));
+}
+
 #[tokio::test]
 async fn test_index_page() {
     let env = setup_with_album();
     let (status, body) = get(env.router, "/").await;
     assert_eq!(status, StatusCode::OK);
-    insta::assert_snapshot!("index_page", body);
+    assert_page_snapshot("index_page", &body);
 }
 
 #[tokio::test]
@@ -109,7 +118,7 @@ async fn test_index_empty() {
     let env = setup_empty();
     let (status, body) = get(env.router, "/").await;
     assert_eq!(status, StatusCode::OK);
-    insta::assert_snapshot!("index_empty", body);
+    assert_page_snapshot("index_empty", &body);
 }
 
 #[tokio::test]
@@ -117,7 +126,7 @@ async fn test_album_page() {
     let env = setup_with_album();
     let (status, body) = get(env.router, "/album/test-album").await;
     assert_eq!(status, StatusCode::OK);
-    insta::assert_snapshot!("album_page", body);
+    assert_page_snapshot("album_page", &body);
 }
 
 #[tokio::test]
@@ -125,7 +134,7 @@ async fn test_photo_page_first() {
     let env = setup_with_album();
     let (status, body) = get(env.router, "/album/test-album/photo-a.jpg").await;
     assert_eq!(status, StatusCode::OK);
-    insta::assert_snapshot!("photo_page_first", body);
+    assert_page_snapshot("photo_page_first", &body);
 }
 
 #[tokio::test]
@@ -133,7 +142,7 @@ async fn test_photo_page_middle() {
     let env = setup_with_album();
     let (status, body) = get(env.router, "/album/test-album/photo-b.jpg").await;
     assert_eq!(status, StatusCode::OK);
-    insta::assert_snapshot!("photo_page_middle", body);
+    assert_page_snapshot("photo_page_middle", &body);
 }
 
 #[tokio::test]
@@ -141,7 +150,7 @@ async fn test_photo_page_last() {
     let env = setup_with_album();
     let (status, body) = get(env.router, "/album/test-album/photo-c.jpg").await;
     assert_eq!(status, StatusCode::OK);
-    insta::assert_snapshot!("photo_page_last", body);
+    assert_page_snapshot("photo_page_last", &body);
 }
 
 // --- Status code tests ---
@@ -183,26 +192,44 @@ async fn test_serve_photo() {
     assert!(!body.is_empty());
 }
 
-#[tokio::test]
-async fn test_serve_photo_cache_header() {
-    let env = setup_with_album();
-    let response = env
-        .router
-        .oneshot(
-            Request::builder()
-                .uri("/photos/test-album/photo-a.jpg")
-                .body(Body::empty())
-                .unwrap(),
-        )
+async fn cache_control(router: axum::Router, uri: &str) -> String {
+    let response = router
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
         .await
         .unwrap();
-    let cache_control = response
-        .headers()
-        .get("cache-control")
-        .unwrap()
+    assert_eq!(response.status(), StatusCode::OK);
+    response.headers()["cache-control"]
         .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_serve_photo_cache_header() {
+    let env = setup_with_album();
+    let (_, body) = get(env.router.clone(), "/album/test-album/photo-a.jpg").await;
+    let start = body
+        .find("/thumbs/test-album/medium/photo-a.jpg?v=")
         .unwrap();
-    assert_eq!(cache_control, "public, max-age=31536000, immutable");
+    let end = start + body[start..].find('"').unwrap();
+    let (_, version) = body[start..end].split_once('?').unwrap();
+
+    // Only URLs with the current version are cached forever.
+    for unversioned in [
+        "/photos/test-album/photo-a.jpg",
+        "/thumbs/test-album/medium/photo-a.jpg",
+    ] {
+        assert_eq!(
+            cache_control(env.router.clone(), &format!("{}?{}", unversioned, version)).await,
+            "public, max-age=31536000, immutable"
+        );
+        assert_eq!(
+            cache_control(env.router.clone(), unversioned).await,
+            "no-cache"
+        );
+        let stale = format!("{}?v=0123456789abcdef", unversioned);
+        assert_eq!(cache_control(env.router.clone(), &stale).await, "no-cache");
+    }
 }
 
 #[tokio::test]
@@ -234,6 +261,35 @@ async fn test_serve_thumb_medium() {
     assert!(!body.is_empty());
 }
 
+#[tokio::test]
+async fn test_serve_thumb_cover() {
+    let env = setup_with_album();
+    let (status, body, content_type) =
+        get_bytes(env.router, "/thumbs/test-album/cover/photo-a.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/jpeg");
+    let img = image::load_from_memory(&body).unwrap();
+    assert_eq!((img.width(), img.height()), (600, 400));
+}
+
+#[tokio::test]
+async fn test_serve_thumb_cover_focus_cache_dir() {
+    let env = setup_with_album();
+    let data_dir = env._dir.path();
+    fs::write(
+        data_dir.join("photos/test-album/album.toml"),
+        "cover_focus = [0.2, 0.8]\n",
+    )
+    .unwrap();
+    let status = get_status(env.router, "/thumbs/test-album/cover/photo-a.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(
+        data_dir
+            .join("cache/test-album/cover-20-80/photo-a.jpg")
+            .is_file()
+    );
+}
+
 #[tokio::test]
 async fn test_serve_thumb_invalid_size() {
     let env = setup_with_album();
@@ -282,7 +338,7 @@ async fn test_serve_photo_png() {
     let dir = tempfile::tempdir().unwrap();
     let album_dir = dir.path().join("photos").join("test-album");
     fs::create_dir_all(&album_dir).unwrap();
-    fs::write(album_dir.join("photo.png"), &make_minimal_png()).unwrap();
+    fs::write(album_dir.join("photo.png"), make_minimal_png()).unwrap();
 
     let router = kuvasivu::build_router(dir.path(), &dir.path().join("cache"));
     let (status, _, content_type) = get_bytes(router, "/photos/test-album/photo.png").await;
@@ -356,3 +412,930 @@ async fn test_serve_photo_unknown_extension() {
     assert_eq!(status, StatusCode::OK);
     assert_eq!(content_type, "application/octet-stream");
 }
+
+#[tokio::test]
+async fn test_pages_use_fingerprinted_urls() {
+    let env = setup_with_album();
+    let (_, body) = get(env.router, "/album/test-album").await;
+    assert!(body.contains("/thumbs/test-album/medium/photo-a.jpg?v="));
+    assert!(!body.contains("href=\"/static/style.css\""));
+}
+
+#[tokio::test]
+async fn test_serve_static_fingerprinted() {
+    let env = setup_empty();
+    let (_, body) = get(env.router.clone(), "/").await;
+    let start = body.find("/static/style.").unwrap();
+    let end = start + body[start..].find('"').unwrap();
+    let url = &body[start..end];
+
+    let response = env
+        .router
+        .oneshot(Request::builder().uri(url).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/css");
+    assert_eq!(
+        response.headers()["cache-control"],
+        "public, max-age=31536000, immutable"
+    );
+}
+
+#[tokio::test]
+async fn test_serve_static_unversioned() {
+    let env = setup_empty();
+    let response = env
+        .router
+        .oneshot(
+            Request::builder()
+                .uri("/static/style.css")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["cache-control"], "no-cache");
+}
+
+#[tokio::test]
+async fn test_serve_static_missing() {
+    let env = setup_empty();
+    assert_eq!(
+        get_status(env.router, "/static/nope.css").await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_serve_thumb_regenerates_stale() {
+    let dir = tempfile::tempdir().unwrap();
+    let album_dir = dir.path().join("photos").join("test-album");
+    fs::create_dir_all(&album_dir).unwrap();
+
+    // A cached thumbnail that is older than the original must be replaced.
+    let cache_dir = dir.path().join("cache");
+    let thumb_dir = cache_dir.join("test-album").join("small");
+    fs::create_dir_all(&thumb_dir).unwrap();
+    fs::write(thumb_dir.join("photo.jpg"), b"stale").unwrap();
+    let old = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+    fs::File::options()
+        .write(true)
+        .open(thumb_dir.join("photo.jpg"))
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+    fs::copy(fixture_jpg(), album_dir.join("photo.jpg")).unwrap();
+
+    let router = kuvasivu::build_router(dir.path(), &cache_dir);
+    let (status, body, _) = get_bytes(router, "/thumbs/test-album/small/photo.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(body, b"stale");
+}
+
+#[tokio::test]
+async fn test_serve_static_theme_override() {
+    let dir = tempfile::tempdir().unwrap();
+    let theme_dir = dir.path().join("theme").join("static");
+    fs::create_dir_all(&theme_dir).unwrap();
+    fs::create_dir(dir.path().join("photos")).unwrap();
+    fs::write(theme_dir.join("style.css"), "body { color: red; }").unwrap();
+    fs::write(theme_dir.join("logo.svg"), "<svg></svg>").unwrap();
+
+    let router = kuvasivu::build_router(dir.path(), &dir.path().join("cache"));
+    let (status, body) = get(router.clone(), "/static/style.css").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "body { color: red; }");
+
+    let (status, _) = get(router.clone(), "/static/logo.svg").await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Files that aren't overridden fall back to the embedded defaults.
+    let (status, _) = get(router, "/static/favicon.svg").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_theme_template_override() {
+    let env = setup_with_album();
+    let data_dir = env._dir.path();
+    let templates_dir = data_dir.join("theme").join("templates");
+    fs::create_dir_all(&templates_dir).unwrap();
+    fs::write(
+        templates_dir.join("album.html"),
+        "{{ album.title }}: {% for photo in photos %}{{ photo.filename }} {% endfor %}",
+    )
+    .unwrap();
+    let router = kuvasivu::build_router(data_dir, &data_dir.join("cache"));
+
+    let (status, body) = get(router.clone(), "/album/test-album").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "Test Album: photo-a.jpg photo-b.jpg photo-c.jpg ");
+
+    // The photo page gets the same details as the built-in one.
+    fs::write(
+        templates_dir.join("photo.html"),
+        "{% for label, value in details %}{{ label }}={{ value }};{% endfor %}",
+    )
+    .unwrap();
+    let (status, body) = get(router.clone(), "/album/test-album/photo-a.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Camera=FUJIFILM X-T5;"));
+    assert!(body.contains("ISO=125;"));
+
+    // Pages without a theme template use the built-in one.
+    let (status, body) = get(router, "/").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<h1>Albums</h1>"));
+}
+
+#[tokio::test]
+async fn test_theme_template_error() {
+    let env = setup_with_album();
+    let data_dir = env._dir.path();
+    let templates_dir = data_dir.join("theme").join("templates");
+    fs::create_dir_all(&templates_dir).unwrap();
+    fs::write(templates_dir.join("index.html"), "{% if %}").unwrap();
+    let router = kuvasivu::build_router(data_dir, &data_dir.join("cache"));
+
+    assert_eq!(
+        get_status(router, "/").await,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+}
+
+#[tokio::test]
+async fn test_photo_page_follows_album_order() {
+    let env = setup_with_album();
+    let album_dir = env._dir.path().join("photos").join("test-album");
+    fs::write(
+        album_dir.join("album.toml"),
+        "title = \"Test Album\"\norder = [\"photo-c.jpg\"]\nreverse = true\n",
+    )
+    .unwrap();
+
+    // Order is c, b, a: photo-c.jpg is listed first and the rest are reversed.
+    let (status, body) = get(env.router, "/album/test-album/photo-b.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(
+        body.contains("href=\"/album/test-album/photo-c.jpg\" class=\"photo-nav photo-nav-prev")
+    );
+    assert!(
+        body.contains("href=\"/album/test-album/photo-a.jpg\" class=\"photo-nav photo-nav-next")
+    );
+}
+
+async fn get_zip(router: axum::Router, uri: &str) -> zip::ZipArchive<std::io::Cursor<Vec<u8>>> {
+    let (status, body, content_type) = get_bytes(router, uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "application/zip");
+    zip::ZipArchive::new(std::io::Cursor::new(body)).unwrap()
+}
+
+#[tokio::test]
+async fn test_download_disabled_by_default() {
+    let env = setup_with_album();
+    assert_eq!(
+        get_status(env.router, "/album/test-album/download.zip").await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_download_originals() {
+    let env = setup_with_album();
+    let album_dir = env._dir.path().join("photos/test-album");
+    fs::write(album_dir.join("album.toml"), "download = true\n").unwrap();
+
+    let (_, body) = get(env.router.clone(), "/album/test-album").await;
+    assert!(body.contains("href=\"/album/test-album/download.zip\""));
+
+    let mut archive = get_zip(env.router, "/album/test-album/download.zip").await;
+    assert_eq!(archive.len(), 3);
+    let original = fs::read(album_dir.join("photo-a.jpg")).unwrap();
+    let mut entry = archive.by_name("photo-a.jpg").unwrap();
+    let mut contents = Vec::new();
+    std::io::Read::read_to_end(&mut entry, &mut contents).unwrap();
+    assert_eq!(contents, original);
+}
+
+#[tokio::test]
+async fn test_download_thumbnails() {
+    let env = setup_with_album();
+    let album_dir = env._dir.path().join("photos/test-album");
+    fs::write(
+        album_dir.join("album.toml"),
+        "download = true\ndownload_size = \"small\"\n",
+    )
+    .unwrap();
+
+    let mut archive = get_zip(env.router, "/album/test-album/download.zip").await;
+    let mut entry = archive.by_name("photo-b.jpg").unwrap();
+    let mut contents = Vec::new();
+    std::io::Read::read_to_end(&mut entry, &mut contents).unwrap();
+    let img = image::load_from_memory(&contents).unwrap();
+    assert_eq!(img.width().max(img.height()), 400);
+}
+
+#[tokio::test]
+async fn test_language_setting() {
+    let env = setup_with_album();
+    let data_dir = env._dir.path();
+    fs::write(data_dir.join("site.toml"), "language = \"fi\"\n").unwrap();
+    let router = kuvasivu::build_router(data_dir, &data_dir.join("cache"));
+
+    let (status, body) = get(router, "/").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<html lang=\"fi\">"));
+    assert!(body.contains("<h1>Albumit</h1>"));
+}
+
+#[tokio::test]
+async fn test_album_page_placeholders() {
+    let env = setup_with_album();
+    let (_, body) = get(env.router.clone(), "/album/test-album").await;
+    assert!(!body.contains("data:image/png;base64,"));
+
+    // Generating a thumbnail also caches the placeholder.
+    let status = get_status(env.router.clone(), "/thumbs/test-album/small/photo-a.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = get(env.router.clone(), "/album/test-album").await;
+    assert_eq!(body.matches("data:image/png;base64,").count(), 1);
+
+    // The album card gets the placeholder of the cropped cover.
+    let (_, body) = get(env.router.clone(), "/").await;
+    assert!(!body.contains("data:image/png;base64,"));
+    let status = get_status(env.router.clone(), "/thumbs/test-album/cover/photo-a.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, body) = get(env.router, "/").await;
+    assert!(body.contains(
+        "class=\"has-placeholder\" style=\"background-image: url(data:image/png;base64,"
+    ));
+}
+
+#[tokio::test]
+async fn test_album_page_justified_layout() {
+    let env = setup_with_album();
+    let data_dir = env._dir.path();
+    fs::write(data_dir.join("site.toml"), "layout = \"justified\"\n").unwrap();
+    let router = kuvasivu::build_router(data_dir, &data_dir.join("cache"));
+
+    let (status, body) = get(router, "/album/test-album").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body.contains("photo-grid"));
+    assert_eq!(body.matches("<div class=\"photo-row\">").count(), 1);
+    assert_eq!(body.matches("style=\"flex-basis: 33.3333%\"").count(), 3);
+}
+
+#[tokio::test]
+async fn test_album_layout_override() {
+    let env = setup_with_album();
+    let data_dir = env._dir.path();
+    fs::write(
+        data_dir.join("photos/test-album/album.toml"),
+        "layout = \"justified\"\nrow_height = 200\n",
+    )
+    .unwrap();
+    let router = kuvasivu::build_router(data_dir, &data_dir.join("cache"));
+
+    // Three roughly 3:2 photos at 200px don't fill a row, so they keep their height.
+    let (_, body) = get(router, "/album/test-album").await;
+    assert_eq!(body.matches("style=\"flex-basis: 26.4149%\"").count(), 3);
+}
+
+#[tokio::test]
+async fn test_stats_page() {
+    let env = setup_with_album();
+    let (status, body) = get(env.router, "/stats").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<h1>Statistics</h1>"));
+    assert!(body.contains("Photos: 3"));
+    assert!(body.contains(">FUJIFILM X-T5</text>"));
+    assert!(body.contains("<svg"));
+}
+
+#[tokio::test]
+async fn test_stats_page_empty() {
+    let env = setup_empty();
+    let (status, body) = get(env.router, "/stats").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Photos: 0"));
+    assert!(!body.contains("<svg"));
+}
+
+#[tokio::test]
+async fn test_timeline_pages() {
+    let env = setup_with_album();
+
+    let (status, body) = get(env.router.clone(), "/timeline").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<h1>Timeline</h1>"));
+    assert!(body.contains("<a href=\"/timeline/2026\" class=\"timeline-year\">2026</a>"));
+    assert!(body.contains("<a href=\"/timeline/2026/02\">February</a>"));
+
+    let (status, body) = get(env.router.clone(), "/timeline/2026").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<h1>2026</h1>"));
+    assert!(body.contains("<h2><a href=\"/timeline/2026/02\">February 2026</a></h2>"));
+    assert!(body.contains("href=\"/album/test-album/photo-a.jpg\""));
+
+    let (status, body) = get(env.router.clone(), "/timeline/2026/02").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<h1>February 2026</h1>"));
+    assert!(body.contains("<h2>1 February 2026</h2>"));
+    assert_eq!(body.matches("class=\"photo-card\"").count(), 3);
+    assert!(body.contains("<a href=\"/timeline/2026/02\" class=\"current\">"));
+
+    let (status, _) = get(env.router.clone(), "/timeline/2025").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = get(env.router, "/timeline/2026/03").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_timeline_empty() {
+    let env = setup_empty();
+    let (status, body) = get(env.router, "/timeline").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("No photos with a capture date."));
+}
+
+#[tokio::test]
+async fn test_on_this_day() {
+    let env = setup_with_album();
+
+    let (status, body) = get(env.router.clone(), "/on-this-day?date=2027-02-01").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<p class=\"timespan\">1 February</p>"));
+    assert!(body.contains("<h2><a href=\"/timeline/2026/02\">2026</a></h2>"));
+    assert_eq!(body.matches("class=\"photo-card\"").count(), 3);
+
+    // Photos from the current year aren't memories yet.
+    let (status, body) = get(env.router.clone(), "/on-this-day?date=2026-02-01").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("No photos were taken on this day in earlier years."));
+
+    let (status, _) = get(env.router.clone(), "/on-this-day").await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = get(env.router, "/on-this-day?date=yesterday").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_on_this_day_json() {
+    let env = setup_with_album();
+    let (status, body) = get(env.router, "/on-this-day.json?date=2027-02-01").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.starts_with("{\"date\":\"2027-02-01\",\"years\":[{\"year\":2026,\"photos\":["));
+    assert!(body.contains("\"url\":\"/album/test-album/photo-a.jpg\""));
+    assert!(body.contains("\"taken\":\"2026-02-01 15:01:"));
+    assert_eq!(body.matches("\"album\":\"test-album\"").count(), 3);
+}
+
+#[tokio::test]
+async fn test_capture_time_corrections() {
+    let env = setup_empty();
+    let album = env._dir.path().join("photos/tokyo");
+    fs::create_dir_all(&album).unwrap();
+    fs::copy(fixture_jpg(), album.join("a.jpg")).unwrap();
+    fs::write(
+        album.join("album.toml"),
+        "timezone = \"Asia/Tokyo\"\ntime_offset = \"+09:00\"\n",
+    )
+    .unwrap();
+
+    let (_, body) = get(env.router.clone(), "/album/tokyo").await;
+    assert!(body.contains("<p class=\"timespan\">2 February 2026</p>"));
+    let (_, body) = get(env.router.clone(), "/timeline/2026/02").await;
+    assert!(body.contains("2 February 2026"));
+
+    // The settings of the camera model win.
+    fs::write(
+        album.join("album.toml"),
+        "time_offset = \"+09:00\"\n\n[cameras.\"X-T5\"]\ntime_offset = \"-00:02:00\"\n",
+    )
+    .unwrap();
+    let (_, body) = get(env.router, "/album/tokyo").await;
+    assert!(body.contains("<p class=\"timespan\">1 February 2026</p>"));
+}
+
+/// Adds IPTC datasets of the application record, given by number, to a
+/// JPEG in an APP13 segment.
+fn with_iptc(jpeg: &[u8], datasets: &[(u8, &str)]) -> Vec<u8> {
+    let mut iim = Vec::new();
+    for (dataset, value) in datasets {
+        iim.extend([0x1C, 2, *dataset]);
+        iim.extend((value.len() as u16).to_be_bytes());
+        iim.extend(value.as_bytes());
+    }
+    if iim.len() % 2 == 1 {
+        iim.push(0);
+    }
+    let mut segment = b"Photoshop 3.0\08BIM\x04\x04\x00\x00".to_vec();
+    segment.extend((iim.len() as u32).to_be_bytes());
+    segment.extend(iim);
+
+    let mut out = jpeg[..2].to_vec();
+    out.extend([0xFF, 0xED]);
+    out.extend((segment.len() as u16 + 2).to_be_bytes());
+    out.extend(segment);
+    out.extend(&jpeg[2..]);
+    out
+}
+
+#[tokio::test]
+async fn test_iptc_title_and_caption() {
+    let env = setup_empty();
+    let album = env._dir.path().join("photos/press");
+    fs::create_dir_all(&album).unwrap();
+    let jpeg = fs::read(fixture_jpg()).unwrap();
+    let iptc = [
+        (105, "Harbour at dawn"),
+        (120, "Ferries leaving the South Harbour."),
+        (110, "Example Agency"),
+        (25, "harbour"),
+        (25, "ferry"),
+    ];
+    fs::write(album.join("a.jpg"), with_iptc(&jpeg, &iptc)).unwrap();
+
+    let (status, body) = get(env.router.clone(), "/album/press/a.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<title>Harbour at dawn – Press – "));
+    assert!(body.contains("alt=\"Harbour at dawn\""));
+    assert!(body.contains("<h1>Harbour at dawn</h1>"));
+    assert!(body.contains("<p>Ferries leaving the South Harbour.</p>"));
+    assert!(body.contains("<dt>Credit</dt>\n                <dd>Example Agency</dd>"));
+    assert!(body.contains("<dt>Keywords</dt>\n                <dd>harbour, ferry</dd>"));
+
+    // album.toml overrides the IPTC title and caption.
+    fs::write(
+        album.join("album.toml"),
+        "[photos.\"a.jpg\"]\ntitle = \"Morning ferries\"\n",
+    )
+    .unwrap();
+    let (_, body) = get(env.router, "/album/press/a.jpg").await;
+    assert!(body.contains("<h1>Morning ferries</h1>"));
+    assert!(!body.contains("Harbour at dawn"));
+    assert!(body.contains("<p>Ferries leaving the South Harbour.</p>"));
+}
+
+/// Encodes a small JPEG with the given GPS position in its EXIF data.
+fn make_geotagged_jpeg(latitude: (u32, &[u8]), longitude: (u32, &[u8])) -> Vec<u8> {
+    use exif::{Field, In, Rational, Tag, Value};
+
+    let field = |tag, value| Field {
+        tag,
+        ifd_num: In::PRIMARY,
+        value,
+    };
+    let fields = [
+        field(Tag::GPSLatitudeRef, Value::Ascii(vec![latitude.1.to_vec()])),
+        field(
+            Tag::GPSLatitude,
+            Value::Rational(vec![Rational::from((latitude.0, 1))]),
+        ),
+        field(
+            Tag::GPSLongitudeRef,
+            Value::Ascii(vec![longitude.1.to_vec()]),
+        ),
+        field(
+            Tag::GPSLongitude,
+            Value::Rational(vec![Rational::from((longitude.0, 1))]),
+        ),
+    ];
+    let mut writer = exif::experimental::Writer::new();
+    for f in &fields {
+        writer.push_field(f);
+    }
+    let mut tiff = std::io::Cursor::new(Vec::new());
+    writer.write(&mut tiff, false).unwrap();
+    let tiff = tiff.into_inner();
+
+    let mut encoded = Vec::new();
+    image::RgbImage::new(8, 8)
+        .write_to(
+            &mut std::io::Cursor::new(&mut encoded),
+            image::ImageFormat::Jpeg,
+        )
+        .unwrap();
+
+    // Insert an APP1 segment right after the SOI marker.
+    let mut jpeg = encoded[..2].to_vec();
+    jpeg.extend_from_slice(&[0xff, 0xe1]);
+    jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    jpeg.extend_from_slice(b"Exif\0\0");
+    jpeg.extend_from_slice(&tiff);
+    jpeg.extend_from_slice(&encoded[2..]);
+    jpeg
+}
+
+#[tokio::test]
+async fn test_geojson() {
+    let env = setup_with_album();
+    let photos_dir = env._dir.path().join("photos");
+
+    let trip = photos_dir.join("trip");
+    fs::create_dir(&trip).unwrap();
+    fs::write(trip.join("album.toml"), "title = \"Trip\"\n").unwrap();
+    fs::write(
+        trip.join("north.jpg"),
+        make_geotagged_jpeg((60, b"N"), (24, b"E")),
+    )
+    .unwrap();
+    fs::write(
+        trip.join("south.jpg"),
+        make_geotagged_jpeg((33, b"S"), (70, b"W")),
+    )
+    .unwrap();
+
+    let home = photos_dir.join("home");
+    fs::create_dir(&home).unwrap();
+    fs::write(home.join("album.toml"), "location = [61.5, 23.75]\n").unwrap();
+    fs::copy(fixture_jpg(), home.join("a.jpg")).unwrap();
+
+    let secret = photos_dir.join("secret");
+    fs::create_dir(&secret).unwrap();
+    fs::write(secret.join("album.toml"), "location = false\n").unwrap();
+    fs::write(
+        secret.join("hideout.jpg"),
+        make_geotagged_jpeg((10, b"N"), (10, b"E")),
+    )
+    .unwrap();
+
+    let (status, body, content_type) = get_bytes(env.router, "/api/geo.geojson").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "application/geo+json");
+    let body = String::from_utf8(body).unwrap();
+    assert!(body.starts_with("{\"type\":\"FeatureCollection\",\"features\":["));
+    assert!(body.contains("\"coordinates\":[24.0,60.0]"));
+    assert!(body.contains("\"coordinates\":[-70.0,-33.0]"));
+    assert!(body.contains("\"coordinates\":[23.75,61.5]"));
+    assert!(body.contains("\"album_title\":\"Trip\""));
+    assert!(body.contains("\"url\":\"/album/trip/north.jpg\""));
+    assert!(!body.contains("secret"));
+    // The fixture photos of the test album have no GPS data.
+    assert!(!body.contains("test-album"));
+    assert_eq!(body.matches("\"type\":\"Feature\"").count(), 3);
+}
+
+#[tokio::test]
+async fn test_reverse_geocoding() {
+    let dir = tempfile::tempdir().unwrap();
+    let geonames = dir.path().join("geonames");
+    fs::create_dir(&geonames).unwrap();
+    fs::write(
+        geonames.join("cities15000.txt"),
+        "658225\tHelsinki\tHelsinki\t\t60.16952\t24.93545\tP\tPPLC\tFI\t\t01\t091\t\t\t558457\t\t26\tEurope/Helsinki\t2023-01-01\n",
+    )
+    .unwrap();
+    fs::write(
+        geonames.join("countryInfo.txt"),
+        "#ISO\tISO3\tISO-Numeric\tfips\tCountry\nFI\tFIN\t246\tFI\tFinland\n",
+    )
+    .unwrap();
+
+    let album = dir.path().join("photos/trip");
+    fs::create_dir_all(&album).unwrap();
+    fs::write(
+        album.join("a.jpg"),
+        make_geotagged_jpeg((60, b"N"), (25, b"E")),
+    )
+    .unwrap();
+    fs::write(
+        album.join("b.jpg"),
+        make_geotagged_jpeg((10, b"N"), (10, b"E")),
+    )
+    .unwrap();
+    fs::copy(fixture_jpg(), album.join("c.jpg")).unwrap();
+
+    let cache_dir = dir.path().join("cache");
+    let router = kuvasivu::build_router(dir.path(), &cache_dir);
+
+    let (_, body) = get(router.clone(), "/album/trip/a.jpg").await;
+    assert!(body.contains("<dt>Location</dt>\n                <dd>Helsinki, Finland</dd>"));
+    assert_eq!(
+        fs::read_to_string(cache_dir.join("trip/place/a.jpg.txt")).unwrap(),
+        "Helsinki, Finland"
+    );
+    let (_, body) = get(router.clone(), "/album/trip/b.jpg").await;
+    assert!(!body.contains("<dt>Location</dt>"));
+
+    let (_, body) = get(router.clone(), "/album/trip").await;
+    assert!(body.contains("<p class=\"album-location\">Helsinki, Finland</p>"));
+
+    // Photos of albums kept off the map don't get a place either.
+    fs::write(album.join("album.toml"), "location = false\n").unwrap();
+    let (_, body) = get(router, "/album/trip").await;
+    assert!(!body.contains("album-location"));
+}
+
+#[tokio::test]
+async fn test_hidden_album_originals_have_no_gps() {
+    let env = setup_empty();
+    let photos_dir = env._dir.path().join("photos");
+    let read_exif = |data: Vec<u8>| {
+        exif::Reader::new()
+            .read_from_container(&mut std::io::Cursor::new(data))
+            .unwrap()
+    };
+    let has_position = |exif: &exif::Exif| {
+        exif.get_field(exif::Tag::GPSLatitude, exif::In::PRIMARY)
+            .is_some()
+    };
+
+    let trip = photos_dir.join("trip");
+    fs::create_dir_all(&trip).unwrap();
+    fs::write(
+        trip.join("a.jpg"),
+        make_geotagged_jpeg((60, b"N"), (24, b"E")),
+    )
+    .unwrap();
+    let (_, body, _) = get_bytes(env.router.clone(), "/photos/trip/a.jpg").await;
+    assert!(has_position(&read_exif(body)));
+
+    let secret = photos_dir.join("secret");
+    fs::create_dir_all(&secret).unwrap();
+    fs::write(
+        secret.join("album.toml"),
+        "location = false\ndownload = true\ndownload_size = \"original\"\n",
+    )
+    .unwrap();
+    fs::write(
+        secret.join("a.jpg"),
+        make_geotagged_jpeg((60, b"N"), (24, b"E")),
+    )
+    .unwrap();
+    fs::copy(fixture_jpg(), secret.join("b.jpg")).unwrap();
+
+    let (status, body, content_type) = get_bytes(env.router.clone(), "/photos/secret/a.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/jpeg");
+    assert!(!has_position(&read_exif(body.clone())));
+    assert!(image::load_from_memory(&body).is_ok());
+    // The rest of the EXIF data stays.
+    let (_, body, _) = get_bytes(env.router.clone(), "/photos/secret/b.jpg").await;
+    let exif = read_exif(body);
+    assert!(
+        exif.get_field(exif::Tag::Model, exif::In::PRIMARY)
+            .is_some()
+    );
+
+    let mut archive = get_zip(env.router.clone(), "/album/secret/download.zip").await;
+    let mut entry = archive.by_name("a.jpg").unwrap();
+    let mut data = Vec::new();
+    std::io::Read::read_to_end(&mut entry, &mut data).unwrap();
+    assert!(!has_position(&read_exif(data)));
+
+    // A mistake in another setting doesn't reveal the positions.
+    fs::write(
+        secret.join("album.toml"),
+        "location = false\ntimezone = \"Mars/Olympus\"\n",
+    )
+    .unwrap();
+    let (_, body, _) = get_bytes(env.router.clone(), "/photos/secret/a.jpg").await;
+    assert!(!has_position(&read_exif(body)));
+    let (_, body) = get(env.router, "/api/geo.geojson").await;
+    assert!(body.contains("/album/trip/a.jpg"));
+    assert!(!body.contains("secret"));
+}
+
+#[tokio::test]
+async fn test_gpx_geotagging() {
+    let env = setup_empty();
+    let album = env._dir.path().join("photos/walk");
+    fs::create_dir_all(&album).unwrap();
+    // The fixture photo was taken at 15:01 on 1 February 2026.
+    fs::copy(fixture_jpg(), album.join("a.jpg")).unwrap();
+    fs::write(
+        album.join("b.jpg"),
+        make_geotagged_jpeg((10, b"N"), (10, b"E")),
+    )
+    .unwrap();
+    fs::write(
+        album.join("walk.gpx"),
+        r#"<?xml version="1.0"?>
+<gpx version="1.1" xmlns="http://www.topografix.com/GPX/1/1"><trk><trkseg>
+<trkpt lat="60.5" lon="24.5"><time>2026-02-01T12:55:00Z</time></trkpt>
+<trkpt lat="60.5" lon="24.5"><time>2026-02-01T13:05:00Z</time></trkpt>
+</trkseg></trk></gpx>"#,
+    )
+    .unwrap();
+
+    // Without a time zone the camera is taken to be on UTC, two hours after
+    // the track ends.
+    let (_, body, _) = get_bytes(env.router.clone(), "/api/geo.geojson").await;
+    let body = String::from_utf8(body).unwrap();
+    assert_eq!(body.matches("\"type\":\"Feature\"").count(), 1);
+    assert!(body.contains("\"coordinates\":[10.0,10.0]"));
+
+    fs::write(album.join("album.toml"), "timezone = \"+02:00\"\n").unwrap();
+    let (_, body, _) = get_bytes(env.router.clone(), "/api/geo.geojson").await;
+    let body = String::from_utf8(body).unwrap();
+    assert_eq!(body.matches("\"type\":\"Feature\"").count(), 2);
+    assert!(body.contains("\"coordinates\":[24.5,60.5]"));
+    // Embedded GPS data wins over the track.
+    assert!(body.contains("\"coordinates\":[10.0,10.0]"));
+
+    // The clock corrections of the camera apply to the track too: ten
+    // minutes later is past the end of the track.
+    fs::write(
+        album.join("album.toml"),
+        "timezone = \"+02:00\"\n\n[cameras.\"X-T5\"]\ntime_offset = \"+00:10:00\"\n",
+    )
+    .unwrap();
+    let (_, body, _) = get_bytes(env.router, "/api/geo.geojson").await;
+    let body = String::from_utf8(body).unwrap();
+    assert_eq!(body.matches("\"type\":\"Feature\"").count(), 1);
+}
+
+#[tokio::test]
+async fn test_map_page_and_tiles() {
+    let env = setup_empty();
+    let (status, body) = get(env.router.clone(), "/map").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("<h1>Map</h1>"));
+    assert!(body.contains("data-tiles=\"/tiles/{z}/{x}/{y}.png\""));
+
+    let tile_dir = env._dir.path().join("tiles/3/4");
+    fs::create_dir_all(&tile_dir).unwrap();
+    fs::write(tile_dir.join("2.png"), make_minimal_png()).unwrap();
+    let (status, _, content_type) = get_bytes(env.router.clone(), "/tiles/3/4/2.png").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/png");
+    assert_eq!(
+        get_status(env.router, "/tiles/3/4/9.png").await,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_map_tile_url_setting() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("photos")).unwrap();
+    fs::write(
+        dir.path().join("site.toml"),
+        "map_tiles = \"https://tile.example.org/{z}/{x}/{y}.png\"\nmap_attribution = \"&copy; <a href=\\\"https://example.org\\\">Example</a>\"\n",
+    )
+    .unwrap();
+    let router = kuvasivu::build_router(dir.path(), &dir.path().join("cache"));
+    let (_, body) = get(router, "/map").await;
+    assert!(body.contains("data-tiles=\"https://tile.example.org/{z}/{x}/{y}.png\""));
+    assert!(body.contains(
+        "<div class=\"map-attribution\">&copy; <a href=\"https://example.org\">Example</a></div>"
+    ));
+}
+
+/// Wraps a JPEG into a minimal Fujifilm RAF container.
+fn make_raf(jpeg: &[u8]) -> Vec<u8> {
+    let mut raf = b"FUJIFILMCCD-RAW ".to_vec();
+    raf.resize(84, 0);
+    raf.extend_from_slice(&100u32.to_be_bytes());
+    raf.extend_from_slice(&(jpeg.len() as u32).to_be_bytes());
+    raf.resize(100, 0);
+    raf.extend_from_slice(jpeg);
+    raf
+}
+
+#[tokio::test]
+async fn test_raw_album() {
+    let dir = tempfile::tempdir().unwrap();
+    let album_dir = dir.path().join("photos/raw-album");
+    fs::create_dir_all(&album_dir).unwrap();
+    fs::write(album_dir.join("album.toml"), "download = true\n").unwrap();
+    let fixture = fs::read(fixture_jpg()).unwrap();
+    fs::write(album_dir.join("DSCF0199.RAF"), make_raf(&fixture)).unwrap();
+    let cache_dir = dir.path().join("cache");
+    let router = kuvasivu::build_router(dir.path(), &cache_dir);
+
+    let (status, body) = get(router.clone(), "/album/raw-album").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("/thumbs/raw-album/medium/DSCF0199.RAF?v="));
+    assert!(body.contains("width=\"1200\""));
+
+    let (status, body) = get(router.clone(), "/album/raw-album/DSCF0199.RAF").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("FUJIFILM X-T5"));
+
+    let (status, bytes, content_type) =
+        get_bytes(router.clone(), "/photos/raw-album/DSCF0199.RAF").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/jpeg");
+    assert_eq!(bytes, fixture);
+
+    let (status, bytes, content_type) =
+        get_bytes(router.clone(), "/thumbs/raw-album/small/DSCF0199.RAF").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/jpeg");
+    let img = image::load_from_memory(&bytes).unwrap();
+    assert_eq!(img.width().max(img.height()), 400);
+
+    let mut archive = get_zip(router, "/album/raw-album/download.zip").await;
+    assert_eq!(archive.len(), 1);
+    assert!(archive.by_name("DSCF0199.jpg").is_ok());
+}
+
+#[cfg(all(feature = "tiff", feature = "gif"))]
+#[tokio::test]
+async fn test_tiff_and_animated_gif() {
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, DynamicImage, Frame, ImageBuffer, Rgb, Rgba, RgbaImage};
+
+    let dir = tempfile::tempdir().unwrap();
+    let album_dir = dir.path().join("photos/scans");
+    fs::create_dir_all(&album_dir).unwrap();
+    DynamicImage::ImageRgb16(ImageBuffer::from_pixel(60, 40, Rgb([65535, 0, 0])))
+        .save(album_dir.join("scan.tif"))
+        .unwrap();
+    let frames = (0..2).map(|i| {
+        Frame::from_parts(
+            RgbaImage::from_pixel(30, 20, Rgba([0, i * 100, 0, 255])),
+            0,
+            0,
+            Delay::from_numer_denom_ms(100, 1),
+        )
+    });
+    let file = fs::File::create(album_dir.join("anim.gif")).unwrap();
+    GifEncoder::new(file).encode_frames(frames).unwrap();
+    let cache_dir = dir.path().join("cache");
+    let router = kuvasivu::build_router(dir.path(), &cache_dir);
+
+    let (status, bytes, content_type) =
+        get_bytes(router.clone(), "/thumbs/scans/medium/scan.tif").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/jpeg");
+    let thumb = image::load_from_memory(&bytes).unwrap();
+    assert_eq!(thumb.color(), image::ColorType::Rgb8);
+
+    let (_, _, content_type) = get_bytes(router.clone(), "/thumbs/scans/small/anim.gif").await;
+    assert_eq!(content_type, "image/png");
+
+    // Animations are shown as-is on the photo page.
+    let (_, body) = get(router.clone(), "/album/scans/anim.gif").await;
+    assert!(body.contains("<img src=\"/photos/scans/anim.gif?v="));
+    let (_, body) = get(router, "/album/scans/scan.tif").await;
+    assert!(body.contains("<img src=\"/thumbs/scans/medium/scan.tif?v="));
+}
+
+#[cfg(feature = "gif")]
+#[tokio::test]
+async fn test_download_same_stem() {
+    use image::{DynamicImage, ImageBuffer, Rgb};
+
+    let dir = tempfile::tempdir().unwrap();
+    let album_dir = dir.path().join("photos/pair");
+    fs::create_dir_all(&album_dir).unwrap();
+    let img = DynamicImage::ImageRgb8(ImageBuffer::from_pixel(60, 40, Rgb([200, 0, 0])));
+    img.save(album_dir.join("x.png")).unwrap();
+    img.save(album_dir.join("x.gif")).unwrap();
+    fs::write(
+        album_dir.join("album.toml"),
+        "download = true\ndownload_size = \"small\"\n",
+    )
+    .unwrap();
+    let router = kuvasivu::build_router(dir.path(), &dir.path().join("cache"));
+
+    // The thumbnails of both are PNGs.
+    let archive = get_zip(router, "/album/pair/download.zip").await;
+    let mut names: Vec<String> = archive
+        .file_names()
+        .map(|name| name.unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["x-2.png", "x.png"]);
+}
+
+#[cfg(all(feature = "tiff", feature = "gif"))]
+#[tokio::test]
+async fn test_download_converted_thumbnails() {
+    use image::{DynamicImage, ImageBuffer, Rgb};
+
+    let dir = tempfile::tempdir().unwrap();
+    let album_dir = dir.path().join("photos/scans");
+    fs::create_dir_all(&album_dir).unwrap();
+    let img = DynamicImage::ImageRgb8(ImageBuffer::from_pixel(60, 40, Rgb([200, 0, 0])));
+    img.save(album_dir.join("scan.tif")).unwrap();
+    img.save(album_dir.join("anim.gif")).unwrap();
+    fs::write(
+        album_dir.join("album.toml"),
+        "download = true\ndownload_size = \"medium\"\n",
+    )
+    .unwrap();
+    let router = kuvasivu::build_router(dir.path(), &dir.path().join("cache"));
+
+    let mut archive = get_zip(router, "/album/scans/download.zip").await;
+    let mut names: Vec<String> = archive
+        .file_names()
+        .map(|name| name.unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["anim.png", "scan.jpg"]);
+    for (name, format) in [
+        ("anim.png", image::ImageFormat::Png),
+        ("scan.jpg", image::ImageFormat::Jpeg),
+    ] {
+        let mut contents = Vec::new();
+        std::io::Read::read_to_end(&mut archive.by_name(name).unwrap(), &mut contents).unwrap();
+        assert_eq!(image::guess_format(&contents).unwrap(), format);
+    }
+}